@@ -15,7 +15,11 @@
  */
 
 #![cfg_attr(docs_rs, feature(doc_cfg))]
-#![doc = include_str!("../README.md")]
+// The examples of the README use every feature, run them with `--all-features`
+#![cfg_attr(
+    all(feature = "derive", feature = "serde", feature = "env"),
+    doc = include_str!("../README.md")
+)]
 
 #[cfg(feature = "derive")]
 #[cfg_attr(docs_rs, doc(cfg(feature = "derive")))]
@@ -26,8 +30,18 @@ mod proc_macro {
 #[cfg(feature = "derive")]
 pub use proc_macro::EmptyType;
 
-pub use empty_type_traits::{Container, Empty, EmptyType, Fallible, Optional};
+pub use empty_type_traits::{
+    parse_override, Changes, Container, Diagnostic, Diff, Empty, EmptyType, Fallible, FallibleEach,
+    Field, FieldErrors, FieldPath, FieldPaths, Optional, OverrideError, Patch, Provenance,
+    ResolveError, SetPath, Traced,
+};
 
 #[cfg(feature = "serde")]
 #[cfg_attr(docs_rs, doc(cfg(feature = "serde")))]
-pub use empty_type_traits::deserialize_empty;
+pub use empty_type_traits::{
+    deserialize_empty, deserialize_hinted, deserialize_override, DeserializeEach, DeserializeHinted,
+};
+
+#[cfg(feature = "env")]
+#[cfg_attr(docs_rs, doc(cfg(feature = "env")))]
+pub use empty_type_traits::{Env, EnvError, FromEnv};
@@ -0,0 +1,106 @@
+/*
+ * Copyright [2022] [Kevin Velasco]
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use empty_type::{EnvError, OverrideError, ResolveError};
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::io;
+use std::path::PathBuf;
+
+/// A source which failed to load
+#[derive(Debug)]
+pub enum SourceError {
+    /// A file couldn't be read
+    Io { path: PathBuf, source: io::Error },
+    /// A file isn't in a supported format, judged by its extension
+    UnknownFormat { path: PathBuf },
+    /// A file failed to deserialize
+    Parse {
+        path: PathBuf,
+        source: Box<dyn Error + Send + Sync + 'static>,
+    },
+    /// Environment variables failed to parse
+    Env(EnvError),
+    /// An override failed to apply
+    Override(OverrideError),
+}
+
+impl Display for SourceError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            SourceError::Io { path, source } => {
+                write!(f, "Failed to read `{}`: {}", path.display(), source)
+            }
+            SourceError::UnknownFormat { path } => {
+                write!(f, "Unknown format of `{}`", path.display())
+            }
+            SourceError::Parse { path, source } => {
+                write!(f, "Failed to parse `{}`: {}", path.display(), source)
+            }
+            SourceError::Env(error) => Display::fmt(error, f),
+            SourceError::Override(error) => Display::fmt(error, f),
+        }
+    }
+}
+
+impl Error for SourceError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SourceError::Io { source, .. } => Some(source),
+            SourceError::Parse { source, .. } => Some(source.as_ref()),
+            SourceError::Env(error) => Some(error),
+            SourceError::Override(error) => Some(error),
+            SourceError::UnknownFormat { .. } => None,
+        }
+    }
+}
+
+/// A configuration which failed to load
+#[derive(Debug)]
+pub enum LoadError {
+    /// Sources which failed to load. Every source is loaded before failing,
+    /// and the configuration isn't resolved when any of them failed.
+    Sources(Vec<SourceError>),
+    /// The layered configuration failed to resolve, e.g. because no source
+    /// set a required field
+    Resolve(ResolveError),
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Sources(errors) if errors.len() == 1 => Display::fmt(&errors[0], f),
+            LoadError::Sources(errors) => {
+                write!(f, "Failed to load {} sources:", errors.len())?;
+                for error in errors {
+                    write!(f, "\n- {}", error)?;
+                }
+                Ok(())
+            }
+            LoadError::Resolve(error) => Display::fmt(error, f),
+        }
+    }
+}
+
+impl Error for LoadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LoadError::Sources(errors) if errors.len() == 1 => Some(&errors[0]),
+            LoadError::Sources(_) => None,
+            LoadError::Resolve(error) => Some(error),
+        }
+    }
+}
@@ -0,0 +1,237 @@
+/*
+ * Copyright [2022] [Kevin Velasco]
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::{LoadError, SourceError};
+use empty_type::{Container, Empty, EmptyType, FromEnv, Provenance, SetPath, Traced};
+use serde::de::DeserializeOwned;
+use std::ffi::OsStr;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+/// Layers sources of a configuration into an [`Empty<T>`] and resolves it.
+///
+/// Sources are layered in the order they're added, each one over the ones
+/// before it, so fields set by a later source win. Every source is read as
+/// it's added, and the sources which fail are reported together by
+/// [`Loader::load`]. The source which set each field is kept and returned by
+/// [`Loader::load_with_provenance`].
+///
+/// ```text
+/// let config = Loader::<Config>::new()
+///     .defaults(Config::default().into_empty())
+///     .file("config.json")
+///     .optional_file("config.local.json")
+///     .env("APP")
+///     .overrides(cli.overrides)
+///     .load()?;
+/// ```
+pub struct Loader<T>
+where
+    T: EmptyType,
+{
+    layered: Traced<T>,
+    errors: Vec<SourceError>,
+}
+
+impl<T> Loader<T>
+where
+    T: EmptyType,
+{
+    pub fn new() -> Self {
+        Self {
+            layered: Traced::new(),
+            errors: vec![],
+        }
+    }
+
+    /// Layers values compiled into the program, e.g. the defaults of the
+    /// configuration. Their source is `default`.
+    pub fn defaults(self, defaults: Empty<T>) -> Self {
+        self.layer(Ok(defaults), |_| "default".to_owned())
+    }
+
+    /// Layers a file. Its format is detected by its extension: `.json`, and
+    /// `.toml` with the `toml` feature. Its source is its path.
+    pub fn file(self, path: impl AsRef<Path>) -> Self
+    where
+        T::Container: DeserializeOwned,
+    {
+        let path = path.as_ref();
+        let source = path.display().to_string();
+        self.layer(read_file(path), |_| source.clone())
+    }
+
+    /// Layers a file if it exists, e.g. an override of the configuration on
+    /// a developer's machine. A missing file is skipped whatever its format.
+    pub fn optional_file(self, path: impl AsRef<Path>) -> Self
+    where
+        T::Container: DeserializeOwned,
+    {
+        let path = path.as_ref();
+        if let Ok(false) = path.try_exists() {
+            return self;
+        }
+
+        match read_file(path) {
+            // the file was removed after it was checked
+            Err(SourceError::Io { source, .. }) if source.kind() == ErrorKind::NotFound => self,
+            layer => {
+                let source = path.display().to_string();
+                self.layer(layer, |_| source.clone())
+            }
+        }
+    }
+
+    /// Layers the environment variables of the process under `prefix`, see
+    /// [`Empty::from_env`]. The source of each field is its variable.
+    pub fn env(self, prefix: &str) -> Self
+    where
+        T::Container: FromEnv,
+    {
+        let layer = Empty::<T>::from_env(prefix).map_err(SourceError::Env);
+        self.layer(layer, |path| env_variable(prefix, path))
+    }
+
+    /// Like [`Loader::env`], reading from `vars` rather than from the
+    /// environment of the process
+    pub fn env_vars<I, K, V>(self, prefix: &str, vars: I) -> Self
+    where
+        T::Container: FromEnv,
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        let layer = Empty::<T>::from_env_vars(prefix, vars).map_err(SourceError::Env);
+        self.layer(layer, |path| env_variable(prefix, path))
+    }
+
+    /// Layers overrides of the form `path=value`, e.g. from `--set` on a
+    /// command line, see [`Empty::apply_overrides`]. Their source is
+    /// `override`.
+    pub fn overrides<I, S>(mut self, overrides: I) -> Self
+    where
+        T::Container: SetPath,
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut layer = T::new_empty();
+        if let Err(errors) = layer.apply_overrides(overrides) {
+            self.errors
+                .extend(errors.into_iter().map(SourceError::Override));
+        }
+        self.layer(Ok(layer), |_| "override".to_owned())
+    }
+
+    /// The layered container, without resolving it. Useful to inspect its
+    /// diagnostics or to layer it further.
+    pub fn load_empty(self) -> Result<Empty<T>, LoadError> {
+        self.load_traced().map(|traced| traced.into_parts().0)
+    }
+
+    /// Resolves the layered configuration
+    pub fn load(self) -> Result<T, LoadError>
+    where
+        T::Container: Container<Value = T>,
+    {
+        self.load_with_provenance().map(|(config, _)| config)
+    }
+
+    /// Resolves the layered configuration along with the source which set
+    /// each of its fields, e.g. to report the effective configuration
+    pub fn load_with_provenance(self) -> Result<(T, Provenance), LoadError>
+    where
+        T::Container: Container<Value = T>,
+    {
+        self.load_traced()?
+            .try_resolve()
+            .map_err(LoadError::Resolve)
+    }
+
+    fn load_traced(self) -> Result<Traced<T>, LoadError> {
+        if self.errors.is_empty() {
+            Ok(self.layered)
+        } else {
+            Err(LoadError::Sources(self.errors))
+        }
+    }
+
+    fn layer<S>(mut self, layer: Result<Empty<T>, SourceError>, source: S) -> Self
+    where
+        S: Fn(&str) -> String,
+    {
+        match layer {
+            Ok(layer) => self.layered = self.layered.overlay_with(layer, source),
+            Err(error) => self.errors.push(error),
+        }
+        self
+    }
+}
+
+impl<T> Default for Loader<T>
+where
+    T: EmptyType,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// The variable a field is read from, e.g. `APP_DATABASE__HOST` for
+// `database.host`. Mirrors the naming of `FromEnv`.
+fn env_variable(prefix: &str, path: &str) -> String {
+    let name = path
+        .split('.')
+        .map(|field| field.trim_start_matches("r#").to_uppercase())
+        .collect::<Vec<_>>()
+        .join("__");
+
+    if prefix.is_empty() {
+        name
+    } else {
+        format!("{}_{}", prefix, name)
+    }
+}
+
+fn read_file<T>(path: &Path) -> Result<Empty<T>, SourceError>
+where
+    T: EmptyType,
+    T::Container: DeserializeOwned,
+{
+    let format = path
+        .extension()
+        .and_then(OsStr::to_str)
+        .map(str::to_ascii_lowercase);
+    let parse: fn(&str) -> Result<Empty<T>, Box<_>> = match format.as_deref() {
+        Some("json") => |text| serde_json::from_str(text).map_err(Box::from),
+        #[cfg(feature = "toml")]
+        Some("toml") => |text| toml::from_str(text).map_err(Box::from),
+        _ => {
+            return Err(SourceError::UnknownFormat {
+                path: path.to_owned(),
+            })
+        }
+    };
+
+    let text = fs::read_to_string(path).map_err(|source| SourceError::Io {
+        path: path.to_owned(),
+        source,
+    })?;
+    parse(&text).map_err(|source| SourceError::Parse {
+        path: path.to_owned(),
+        source,
+    })
+}
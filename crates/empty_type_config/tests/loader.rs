@@ -0,0 +1,249 @@
+/*
+ * Copyright [2022] [Kevin Velasco]
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use empty_type::EmptyType;
+use empty_type_config::{LoadError, Loader, SourceError};
+use serde::Deserialize;
+
+#[derive(EmptyType, Deserialize, Debug)]
+#[empty(deserialize, env, overrides)]
+struct Config {
+    name: String,
+    port: u16,
+    #[empty(nested)]
+    database: Database,
+}
+
+#[derive(EmptyType, Deserialize, Debug)]
+#[empty(deserialize, env, overrides)]
+struct Database {
+    host: String,
+    pool: u32,
+}
+
+fn fixture(name: &str) -> String {
+    format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+fn source_errors(error: LoadError) -> Vec<SourceError> {
+    match error {
+        LoadError::Sources(errors) => errors,
+        LoadError::Resolve(error) => panic!("expected sources to fail, found {}", error),
+    }
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn later_sources_win() {
+    let mut defaults = Config::new_empty();
+    defaults.name = Some("default".to_owned());
+    defaults.port = Some(1);
+
+    let config = Loader::<Config>::new()
+        .defaults(defaults)
+        .file(fixture("base.json"))
+        .file(fixture("local.toml"))
+        .env_vars("APP", vec![("APP_DATABASE__POOL", "16")])
+        .overrides(["port=8080"])
+        .load()
+        .unwrap();
+
+    assert_eq!(config.name, "service");
+    assert_eq!(config.port, 8080);
+    assert_eq!(config.database.host, "db.local");
+    assert_eq!(config.database.pool, 16);
+}
+
+#[test]
+fn missing_optional_files_are_skipped() {
+    let config = Loader::<Config>::new()
+        .file(fixture("base.json"))
+        .optional_file(fixture("missing.json"))
+        .load()
+        .unwrap();
+
+    assert_eq!(config.port, 80);
+
+    // the format of a file which doesn't exist doesn't matter
+    let config = Loader::<Config>::new()
+        .file(fixture("base.json"))
+        .optional_file(fixture("missing.yaml"))
+        .load()
+        .unwrap();
+
+    assert_eq!(config.port, 80);
+}
+
+#[test]
+fn missing_files_fail_to_load() {
+    let error = Loader::<Config>::new()
+        .file(fixture("missing.json"))
+        .load()
+        .unwrap_err();
+
+    match source_errors(error).as_slice() {
+        [SourceError::Io { path, .. }] => assert!(path.ends_with("missing.json")),
+        other => panic!("expected a missing file, found {:?}", other),
+    }
+}
+
+#[test]
+fn files_of_unknown_formats_fail_to_load() {
+    let error = Loader::<Config>::new()
+        .optional_file(fixture("config.ini"))
+        .load()
+        .unwrap_err();
+
+    assert!(matches!(
+        source_errors(error).as_slice(),
+        [SourceError::UnknownFormat { .. }]
+    ));
+}
+
+#[test]
+fn malformed_files_are_reported_by_path() {
+    let error = Loader::<Config>::new()
+        .file(fixture("broken.json"))
+        .load()
+        .unwrap_err();
+
+    let message = error.to_string();
+    assert!(message.starts_with("Failed to parse `"));
+    assert!(message.contains("broken.json"));
+}
+
+#[test]
+fn every_failed_source_is_reported() {
+    let error = Loader::<Config>::new()
+        .file(fixture("base.json"))
+        .file(fixture("missing.json"))
+        .env_vars("APP", vec![("APP_PORT", "http")])
+        .overrides(["database.port=5432", "name=api"])
+        .load()
+        .unwrap_err();
+
+    let errors = source_errors(error);
+    assert_eq!(errors.len(), 3);
+    assert!(matches!(errors[0], SourceError::Io { .. }));
+    assert_eq!(
+        errors[1].to_string(),
+        "Failed to parse `APP_PORT`: invalid digit found in string"
+    );
+    assert_eq!(errors[2].to_string(), "No field at `database.port`");
+}
+
+#[test]
+fn several_failures_are_listed() {
+    let error = Loader::<Config>::new()
+        .file(fixture("missing.json"))
+        .overrides(["port=http"])
+        .load()
+        .unwrap_err();
+
+    assert!(error
+        .to_string()
+        .starts_with("Failed to load 2 sources:\n- Failed to read `"));
+}
+
+#[test]
+fn fields_no_source_sets_fail_to_resolve() {
+    let error = Loader::<Config>::new()
+        .overrides(["name=api", "port=80"])
+        .load()
+        .unwrap_err();
+
+    let error = match error {
+        LoadError::Resolve(error) => error,
+        LoadError::Sources(errors) => panic!("expected to fail resolving, found {:?}", errors),
+    };
+    let paths: Vec<_> = error
+        .failures()
+        .into_iter()
+        .map(|(path, _)| path.to_string())
+        .collect();
+    assert_eq!(paths, ["database.host", "database.pool"]);
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn the_layered_container_can_be_loaded_without_resolving() {
+    let empty = Loader::<Config>::new()
+        .file(fixture("local.toml"))
+        .load_empty()
+        .unwrap();
+
+    assert_eq!(empty.port, Some(8000));
+    assert!(empty.name.is_none());
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn the_source_of_every_field_is_kept() {
+    let mut defaults = Config::new_empty();
+    defaults.name = Some("default".to_owned());
+
+    let (config, provenance) = Loader::<Config>::new()
+        .defaults(defaults)
+        .file(fixture("base.json"))
+        .optional_file(fixture("local.toml"))
+        .env_vars("APP", vec![("APP_DATABASE__POOL", "16")])
+        .overrides(["port=8080"])
+        .load_with_provenance()
+        .unwrap();
+
+    assert_eq!(config.port, 8080);
+    assert_eq!(provenance.source("port"), Some("override"));
+    assert_eq!(
+        provenance.source("database.pool"),
+        Some("APP_DATABASE__POOL")
+    );
+    assert!(provenance
+        .source("database.host")
+        .unwrap()
+        .ends_with("local.toml"));
+    assert!(provenance.source("name").unwrap().ends_with("base.json"));
+    assert_eq!(provenance.len(), 4);
+}
+
+#[derive(EmptyType, Deserialize, Debug)]
+#[empty(deserialize, env, overrides)]
+struct Flags {
+    verbose: bool,
+}
+
+// Defaults with `verbose` turned on
+fn verbose() -> empty_type::Empty<Flags> {
+    let mut defaults = Flags::new_empty();
+    defaults.verbose = Some(true);
+    defaults
+}
+
+#[test]
+fn later_sources_can_turn_a_bool_off() {
+    let flags = Loader::<Flags>::new()
+        .defaults(verbose())
+        .env_vars("APP", vec![("APP_VERBOSE", "false")])
+        .load()
+        .unwrap();
+    assert!(!flags.verbose);
+
+    let flags = Loader::<Flags>::new()
+        .defaults(verbose())
+        .overrides(["verbose=false"])
+        .load()
+        .unwrap();
+    assert!(!flags.verbose);
+}
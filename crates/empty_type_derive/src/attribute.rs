@@ -14,7 +14,11 @@
  * limitations under the License.
  */
 
-use syn::{Attribute, Lit, Meta, NestedMeta, Path};
+use proc_macro2::TokenStream;
+use quote::{ToTokens, TokenStreamExt};
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{parenthesized, token, Attribute, Error, Expr, ExprLit, Ident, Lit, LitStr, Path, Token};
 
 const ATTRIBUTE_NAME: &str = "empty";
 
@@ -22,78 +26,132 @@ pub fn filter_attrs_by_own(attribute: &Attribute) -> bool {
     attribute.path.is_ident(ATTRIBUTE_NAME)
 }
 
-pub fn find_path_of_attribute(attributes: &[Attribute], name: &'static str) -> Option<Path> {
-    attributes
-        .iter()
-        .filter(|x| filter_attrs_by_own(*x))
-        .find_map(|attr| find_path_in_attribute(attr, name))
+/// A single entry of an `#[empty(...)]` attribute.
+/// `fail_safe`, `name = "..."` or `derive(...)`
+pub struct OptionEntry {
+    pub key: Ident,
+    pub value: OptionValue,
 }
 
-fn find_path_in_attribute(attr: &Attribute, name: &'static str) -> Option<Path> {
-    let meta = attr.parse_meta().expect("Expected arguments to attribute");
-    find_path_in_meta(&meta, name)
+pub enum OptionValue {
+    Flag,
+    // Values are expressions rather than literals so that
+    // `default_value = String::from("...")` can be written as is
+    Value(Token![=], Box<Expr>),
+    List(token::Paren, TokenStream),
 }
 
-fn find_path_in_meta(meta: &Meta, name: &'static str) -> Option<Path> {
-    match meta {
-        Meta::Path(path) if path.is_ident(name) => Some(path.clone()),
-        Meta::List(list) => list
-            .nested
-            .iter()
-            .filter_map(|nested| {
-                if let NestedMeta::Meta(meta) = nested {
-                    Some(meta)
-                } else {
-                    None
-                }
-            })
-            .find_map(|meta| find_path_in_meta(meta, name)),
-        Meta::NameValue(named_value) if named_value.path.is_ident(name) => {
-            Some(named_value.path.clone())
+impl OptionEntry {
+    /// Entries like `fail_safe` which are either present or not
+    pub fn flag(&self) -> syn::Result<bool> {
+        match &self.value {
+            OptionValue::Flag => Ok(true),
+            _ => Err(Error::new_spanned(
+                self,
+                format!("`{}` doesn't take a value", self.key),
+            )),
+        }
+    }
+
+    pub fn value(&self) -> syn::Result<&Expr> {
+        match &self.value {
+            OptionValue::Value(_, value) => Ok(value),
+            _ => Err(Error::new_spanned(
+                self,
+                format!(
+                    "`{}` should have a value, e.g. `{} = \"...\"`",
+                    self.key, self.key
+                ),
+            )),
+        }
+    }
+
+    /// Entries like `derive(...)` which wrap a list of tokens
+    pub fn list(&self) -> syn::Result<&TokenStream> {
+        match &self.value {
+            OptionValue::List(_, content) => Ok(content),
+            _ => Err(Error::new_spanned(
+                self,
+                format!(
+                    "`{}` should have arguments, e.g. `{}(...)`",
+                    self.key, self.key
+                ),
+            )),
+        }
+    }
+
+    pub fn str_value(&self) -> syn::Result<&LitStr> {
+        match self.value()? {
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(value),
+                ..
+            }) => Ok(value),
+            other => Err(Error::new_spanned(
+                other,
+                format!("`{}` should be a string literal", self.key),
+            )),
         }
-        _ => None,
     }
 }
 
-#[allow(dead_code)]
-fn get_meta_value(meta: &Meta, key: &Path) -> Option<Lit> {
-    match meta {
-        Meta::Path(_) => None,
-        Meta::List(list) => list
-            .nested
-            .iter()
-            .filter_map(|nested| {
-                if let NestedMeta::Meta(meta) = nested {
-                    Some(meta)
-                } else {
-                    None
-                }
-            })
-            .find_map(move |meta| get_meta_value(meta, &key)),
-
-        Meta::NameValue(named_value) if named_value.path.get_ident() == key.get_ident() => {
-            Some(named_value.lit.clone())
+impl Parse for OptionEntry {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(Lit) {
+            return Err(input.error("expected an option, found a literal"));
         }
-        _ => None,
+
+        let path = input.call(Path::parse_mod_style)?;
+        let key = match path.get_ident() {
+            Some(key) => key.clone(),
+            None => return Err(Error::new_spanned(path, "expected an option name")),
+        };
+
+        let value = if input.peek(Token![=]) {
+            OptionValue::Value(input.parse()?, Box::new(input.parse()?))
+        } else if input.peek(token::Paren) {
+            let content;
+            let paren = parenthesized!(content in input);
+            OptionValue::List(paren, content.parse()?)
+        } else {
+            OptionValue::Flag
+        };
+
+        Ok(Self { key, value })
     }
 }
-#[allow(dead_code)]
-fn get_value_in_attribute(attribute: &Attribute, name: &Path) -> Option<Lit> {
-    let meta = attribute
-        .parse_meta()
-        .expect("Expected attribute to have meta");
-    get_meta_value(&meta, &name)
+
+impl ToTokens for OptionEntry {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.key.to_tokens(tokens);
+        match &self.value {
+            OptionValue::Flag => {}
+            OptionValue::Value(eq, value) => {
+                eq.to_tokens(tokens);
+                value.to_tokens(tokens);
+            }
+            OptionValue::List(paren, content) => {
+                paren.surround(tokens, |tokens| tokens.append_all(content.clone()))
+            }
+        }
+    }
 }
 
-#[allow(dead_code)]
-pub fn get_attribute_value(attributes: &[Attribute], name: &'static str) -> Option<Lit> {
-    let path = find_path_of_attribute(attributes, name)?;
-
-    Some(
-        attributes
-            .iter()
-            .filter(|x| filter_attrs_by_own(*x))
-            .find_map(move |attr| get_value_in_attribute(attr, &path))
-            .expect(concat!(stringify!(name), " should have a value")),
-    )
+/// Every entry of every `#[empty(...)]` attribute in `attributes`
+pub fn option_entries(attributes: &[Attribute]) -> syn::Result<Vec<OptionEntry>> {
+    let mut entries = vec![];
+
+    for attr in attributes.iter().filter(|x| filter_attrs_by_own(x)) {
+        if attr.tokens.is_empty() {
+            return Err(Error::new_spanned(
+                &attr.path,
+                "expected arguments to the attribute, e.g. `#[empty(fail_safe)]`",
+            ));
+        }
+
+        let parsed =
+            attr.parse_args_with(Punctuated::<OptionEntry, Token![,]>::parse_terminated)?;
+        entries.extend(parsed);
+    }
+
+    Ok(entries)
 }
@@ -14,11 +14,136 @@
  * limitations under the License.
  */
 
-use quote::ToTokens;
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote, ToTokens};
 use syn::punctuated::Punctuated;
 
-use crate::find_path_of_attribute;
-use syn::{parse_quote, Field, FieldValue, Fields, Index, Member, PathArguments, Token};
+use crate::options::{FailSafe, FieldDefault, FieldOptions, StrField};
+use crate::serde_names::{rename_all as serde_rename_all, serde_field_name, serde_variant_name};
+use syn::{
+    parse_quote, Error, Expr, Field, FieldValue, Fields, GenericArgument, Index, Member,
+    PathArguments, PathSegment, Token, Type, Variant,
+};
+
+// How generated code reaches the fields of a container. Struct containers
+// access their fields through `self`, enum containers bind the fields of
+// the matched variant by reference. Both carry the `rename_all` rule of the
+// type so fields are named the way serde names them.
+#[derive(Clone, Copy)]
+pub enum FieldAccess<'a> {
+    SelfMember(Option<&'a str>),
+    Binding(&'a Variant, Option<&'a str>),
+}
+
+impl<'a> FieldAccess<'a> {
+    pub fn expr(&self, member: &Member) -> Expr {
+        match self {
+            FieldAccess::SelfMember(_) => parse_quote!(&mut self.#member),
+            FieldAccess::Binding(..) => {
+                let binding = binding_ident(member);
+                parse_quote!(#binding)
+            }
+        }
+    }
+
+    // The path segment of a field, e.g. `"port"` or `"Tcp::port"`. The fields
+    // of a variant follow the `rename_all` rule of the variant.
+    pub fn name(&self, field: &Field, member: &Member) -> TokenStream {
+        let name = match self {
+            FieldAccess::SelfMember(rename_all) => {
+                serde_field_name(&field.attrs, member, *rename_all)
+            }
+            FieldAccess::Binding(variant, rename_all) => format!(
+                "{}::{}",
+                serde_variant_name(variant, *rename_all),
+                serde_field_name(
+                    &field.attrs,
+                    member,
+                    serde_rename_all(&variant.attrs).as_deref()
+                )
+            ),
+        };
+        quote!(#name)
+    }
+}
+
+pub fn binding_ident(member: &Member) -> Ident {
+    prefixed_binding_ident("__self_", member)
+}
+
+// Bindings for the fields of a second value of the same type, e.g. the
+// `other` side of a merge
+pub fn other_binding_ident(member: &Member) -> Ident {
+    prefixed_binding_ident("__other_", member)
+}
+
+// Bindings for the fields of the full value a container is applied to
+pub fn target_binding_ident(member: &Member) -> Ident {
+    prefixed_binding_ident("__target_", member)
+}
+
+// Bindings for the patch opened for each field of a container
+fn patch_binding_ident(member: &Member) -> Ident {
+    prefixed_binding_ident("__patch_", member)
+}
+
+fn prefixed_binding_ident(prefix: &str, member: &Member) -> Ident {
+    match member {
+        Member::Named(ident) => format_ident!("{}{}", prefix, ident),
+        Member::Unnamed(index) => format_ident!("{}{}", prefix, index.index),
+    }
+}
+
+/// Destructures every field in `fields` into its binding.
+/// `{ key: __self_key }` or `{ 0: __self_0 }`
+pub fn bindings_pattern(fields: &Fields) -> TokenStream {
+    bindings_pattern_with(fields, binding_ident)
+}
+
+/// Destructures every field in `fields` into its other binding.
+/// `{ key: __other_key }` or `{ 0: __other_0 }`
+pub fn other_bindings_pattern(fields: &Fields) -> TokenStream {
+    bindings_pattern_with(fields, other_binding_ident)
+}
+
+/// Destructures every field in `fields` into its target binding.
+/// `{ key: __target_key }` or `{ 0: __target_0 }`
+pub fn target_bindings_pattern(fields: &Fields) -> TokenStream {
+    bindings_pattern_with(fields, target_binding_ident)
+}
+
+fn bindings_pattern_with(fields: &Fields, binding: fn(&Member) -> Ident) -> TokenStream {
+    let bindings = map_fields_to_values(fields, |_, member| FieldValue {
+        attrs: vec![],
+        colon_token: Some(Default::default()),
+        expr: {
+            let binding = binding(&member);
+            parse_quote!(#binding)
+        },
+        member,
+    });
+
+    quote! { { #bindings } }
+}
+
+/// Merges every field bound by [`other_bindings_pattern`] into the matching
+/// field of the container
+pub fn create_merged_fields(fields: &Fields, access: FieldAccess) -> Vec<TokenStream> {
+    map_fields_to_values(fields, |_, member| {
+        let accessor = access.expr(&member);
+        let other = other_binding_ident(&member);
+
+        FieldValue {
+            attrs: vec![],
+            colon_token: None,
+            expr: parse_quote!(empty_type::Container::merge(#accessor, #other)),
+            member,
+        }
+    })
+    .into_iter()
+    .map(|f| f.expr.into_token_stream())
+    .collect()
+}
 
 pub fn field_type_is_literally(field: &Field, literally: &'static str) -> bool {
     field.ty.to_token_stream().to_string() == literally
@@ -29,66 +154,577 @@ pub fn wrap_option_in_fallable(field: &mut Field) {
     field.ty = parse_quote!( empty_type::Fallible<#ty> );
 }
 
-pub fn wrap_field_in_option(field: &mut Field) {
-    // we don't do anything to bools. Option<bool> makes little sense to be honest
-    if field_type_is_literally(&field, "bool") {
-        return;
+// Collections which are fail-safe element by element
+pub fn wrap_field_in_fallible_each(field: &mut Field) {
+    let ty = field.ty.clone();
+    field.ty = parse_quote!( empty_type::FallibleEach<#ty> );
+}
+
+// Nested fields hold the container of their type so partial values
+// propagate instead of failing the whole field. Collections and smart
+// pointers hold the container of their elements, e.g. `Vec<Empty<T>>`.
+pub fn wrap_field_in_empty(field: &mut Field) {
+    field.ty = nested_container(&field.ty);
+}
+
+// The shapes of nested types which hold containers of their elements
+enum Nested<'a> {
+    // `Vec`, `VecDeque`, `HashMap` and `BTreeMap`, opened element by element
+    Collection {
+        element: &'a Type,
+        map: bool,
+    },
+    // `Box`, `Rc` and `Arc`
+    Pointer {
+        pointer: &'a Ident,
+        element: &'a Type,
+    },
+    Array(&'a Type),
+    Tuple(Vec<&'a Type>),
+    // any other type is held in its `Empty` container
+    Container,
+}
+
+fn nested_shape(ty: &Type) -> Nested<'_> {
+    match ty {
+        Type::Array(array) => Nested::Array(&array.elem),
+        Type::Tuple(tuple) if !tuple.elems.is_empty() => {
+            Nested::Tuple(tuple.elems.iter().collect())
+        }
+        Type::Path(path) if path.qself.is_none() => {
+            let segment = match path.path.segments.last() {
+                Some(segment) => segment,
+                None => return Nested::Container,
+            };
+            // the element is the last type argument, the value of a map
+            let element = match &segment.arguments {
+                PathArguments::AngleBracketed(args) => match args.args.last() {
+                    Some(GenericArgument::Type(element)) => element,
+                    _ => return Nested::Container,
+                },
+                _ => return Nested::Container,
+            };
+
+            match segment.ident.to_string().as_str() {
+                "Vec" | "VecDeque" => Nested::Collection {
+                    element,
+                    map: false,
+                },
+                "HashMap" | "BTreeMap" => Nested::Collection { element, map: true },
+                "Box" | "Rc" | "Arc" => Nested::Pointer {
+                    pointer: &segment.ident,
+                    element,
+                },
+                _ => Nested::Container,
+            }
+        }
+        _ => Nested::Container,
     }
+}
 
-    // If the field is already an option, we wrap it in a special optional type
-    // which is able to unwrap nested options
-    if let syn::Type::Path(type_path) = field.ty.clone() {
-        if let Some(segment) = type_path.path.segments.last() {
-            if segment.ident == "Option" {
-                let ty = if let PathArguments::AngleBracketed(ref args) = segment.arguments {
-                    args
-                } else {
-                    unreachable!()
+// The container a nested field of type `ty` holds
+fn nested_container(ty: &Type) -> Type {
+    match nested_shape(ty) {
+        Nested::Collection { element, .. } | Nested::Pointer { element, .. } => {
+            let mut ty = ty.clone();
+            if let Some(GenericArgument::Type(last)) = last_type_argument(&mut ty) {
+                *last = nested_container(element);
+            }
+            ty
+        }
+        Nested::Array(element) => {
+            let mut ty = ty.clone();
+            if let Type::Array(array) = &mut ty {
+                *array.elem = nested_container(element);
+            }
+            ty
+        }
+        Nested::Tuple(elements) => {
+            let elements = elements.into_iter().map(nested_container);
+            parse_quote!( (#(#elements,)*) )
+        }
+        Nested::Container => parse_quote!( empty_type::Empty<#ty> ),
+    }
+}
+
+fn last_type_argument(ty: &mut Type) -> Option<&mut GenericArgument> {
+    match ty {
+        Type::Path(path) => match &mut path.path.segments.last_mut()?.arguments {
+            PathArguments::AngleBracketed(args) => args.args.last_mut(),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+// Converts `value` of type `ty` into the container of a nested field
+fn nested_value(ty: &Type, value: Expr) -> Expr {
+    match nested_shape(ty) {
+        Nested::Collection { element, map } => {
+            let container = nested_container(ty);
+            let converted = nested_value(element, parse_quote!(element));
+            if map {
+                parse_quote! {
+                    #value
+                        .into_iter()
+                        .map(|(key, element)| (key, #converted))
+                        .collect::<#container>()
+                }
+            } else {
+                parse_quote! {
+                    #value
+                        .into_iter()
+                        .map(|element| #converted)
+                        .collect::<#container>()
+                }
+            }
+        }
+        Nested::Pointer { pointer, element } => {
+            if pointer == "Box" {
+                let converted = nested_value(element, parse_quote!(element));
+                return parse_quote! {
+                    std::boxed::Box::new({
+                        let element = *#value;
+                        #converted
+                    })
                 };
+            }
 
-                field.ty = parse_quote!( empty_type::Optional#ty );
-                return;
+            let pointer = if pointer == "Rc" {
+                quote!(std::rc::Rc)
+            } else {
+                quote!(std::sync::Arc)
+            };
+            // a shared value is cloned out of the pointer
+            let converted = nested_value(
+                element,
+                parse_quote! {
+                    #pointer::try_unwrap(#value)
+                        .unwrap_or_else(|shared| std::clone::Clone::clone(&*shared))
+                },
+            );
+            parse_quote!(#pointer::new(#converted))
+        }
+        Nested::Array(element) => {
+            let converted = nested_value(element, parse_quote!(element));
+            parse_quote!(#value.map(|element| #converted))
+        }
+        Nested::Tuple(elements) => {
+            let bindings: Vec<_> = (0..elements.len())
+                .map(|index| format_ident!("__element_{}", index))
+                .collect();
+            let converted = elements
+                .into_iter()
+                .zip(&bindings)
+                .map(|(element, binding)| nested_value(element, parse_quote!(#binding)));
+            parse_quote! {
+                match #value {
+                    (#(#bindings,)*) => (#(#converted,)*),
+                }
             }
         }
+        Nested::Container => parse_quote!(empty_type::EmptyType::into_empty(#value)),
+    }
+}
+
+pub fn wrap_field_in_option(field: &mut Field) -> syn::Result<()> {
+    // If the field is already an option, we wrap it in a special optional type
+    // which is able to unwrap nested options
+    if let Some(segment) = option_segment(field) {
+        let ty = if let PathArguments::AngleBracketed(ref args) = segment.arguments {
+            args
+        } else {
+            return Err(Error::new_spanned(
+                &field.ty,
+                "expected `Option` to have a type argument, e.g. `Option<T>`",
+            ));
+        };
+
+        field.ty = parse_quote!( empty_type::Optional#ty );
+        return Ok(());
     }
 
     let ty = field.ty.clone();
     field.ty = parse_quote! { std::option::Option<#ty>};
+    Ok(())
 }
 
-pub fn create_unwraped_fields(fields: &Fields) -> Punctuated<FieldValue, Token![,]> {
-    map_fields_to_values(fields, |field, member| {
-        let attributes = &field.attrs;
-        let output_type = if find_path_of_attribute(attributes, "default").is_some()
-            || find_path_of_attribute(attributes, "fail_safe").is_some()
-        {
-            parse_quote! {
-               empty_type::Container::open_or_default(&mut self.#member)
+pub(crate) fn option_segment(field: &Field) -> Option<&PathSegment> {
+    match &field.ty {
+        Type::Path(type_path) => type_path
+            .path
+            .segments
+            .last()
+            .filter(|segment| segment.ident == "Option"),
+        _ => None,
+    }
+}
+
+/// Opens a patch for every field bound by [`bindings_pattern`] against the
+/// matching field bound by [`target_bindings_pattern`], without writing it
+pub fn create_field_patches(fields: &Fields) -> Vec<TokenStream> {
+    field_members(fields)
+        .iter()
+        .map(|member| {
+            let binding = binding_ident(member);
+            let target = target_binding_ident(member);
+            let patch = patch_binding_ident(member);
+            quote! {
+                let #patch = empty_type::Container::try_patch(#binding, #target)?
             }
-        } else {
-            parse_quote! {
-                empty_type::Container::try_open_with_meta(&mut self.#member, stringify!(#member))?
+        })
+        .collect()
+}
+
+/// Writes the patch of every field into the matching field bound by
+/// [`target_bindings_pattern`], recording what was written in `changes`
+pub fn create_written_patches(fields: &Fields, access: FieldAccess) -> Vec<TokenStream> {
+    fields
+        .iter()
+        .zip(field_members(fields))
+        .map(|(field, member)| {
+            let target = target_binding_ident(&member);
+            let patch = patch_binding_ident(&member);
+            let name = access.name(field, &member);
+            quote! {
+                changes.record(#name, #patch.write(#target))
             }
-        };
+        })
+        .collect()
+}
+
+/// Records the fields set in every field bound by [`bindings_pattern`] under
+/// the name of the field
+pub fn create_set_fields(fields: &Fields, access: FieldAccess) -> Vec<TokenStream> {
+    fields
+        .iter()
+        .zip(field_members(fields))
+        .map(|(field, member)| {
+            let binding = binding_ident(&member);
+            let name = access.name(field, &member);
+            quote! {
+                paths.record(
+                    #name,
+                    empty_type::Container::set_fields(#binding),
+                )
+            }
+        })
+        .collect()
+}
+
+/// Records the missing fields of every field bound by [`bindings_pattern`]
+/// under the name of the field. Fields which fall back to a default are never
+/// missing.
+pub fn create_missing_fields(
+    fields: &Fields,
+    access: FieldAccess,
+) -> syn::Result<Vec<TokenStream>> {
+    let mut missing = vec![];
+    for (field, member) in fields.iter().zip(field_members(fields)) {
+        let options = FieldOptions::from_attributes(&field.attrs)?;
+        if field_default(field, &options).is_some() || options.fail_safe.is_some() {
+            continue;
+        }
+
+        let binding = binding_ident(&member);
+        let name = access.name(field, &member);
+        missing.push(quote! {
+            paths.record(
+                #name,
+                empty_type::Container::missing_fields(#binding),
+            )
+        });
+    }
+
+    Ok(missing)
+}
+
+/// Collects the diagnostics of every field bound by [`bindings_pattern`]
+/// under the name of the field
+pub fn create_diagnosed_fields(fields: &Fields, access: FieldAccess) -> Vec<TokenStream> {
+    fields
+        .iter()
+        .zip(field_members(fields))
+        .map(|(field, member)| {
+            let binding = binding_ident(&member);
+            let name = access.name(field, &member);
+            quote! {
+                diagnostics.extend(
+                    empty_type::Container::diagnostics(#binding)
+                        .into_iter()
+                        .map(|diagnostic| diagnostic.in_field(#name)),
+                )
+            }
+        })
+        .collect()
+}
+
+/// Diffs every field bound by [`bindings_pattern`] against the matching field
+/// bound by [`other_bindings_pattern`]
+pub fn create_diffed_fields(fields: &Fields) -> Punctuated<FieldValue, Token![,]> {
+    map_fields_to_values(fields, |_, member| {
+        let binding = binding_ident(&member);
+        let other = other_binding_ident(&member);
 
         FieldValue {
             attrs: vec![],
-            colon_token: field.colon_token.clone(),
-            expr: output_type,
+            colon_token: Some(Default::default()),
+            expr: parse_quote!(empty_type::Diff::diff(#binding, #other)),
             member,
         }
     })
 }
 
-pub fn create_unwrapped_default_fields(fields: &Fields) -> Punctuated<FieldValue, Token![,]> {
-    map_fields_to_values(fields, |field, member| FieldValue {
-        attrs: vec![],
-        colon_token: field.colon_token.clone(),
-        expr: parse_quote! {
-            empty_type::Container::open_or_default(&mut self.#member)
+/// Sets every field of a container from the matching binding of a full value.
+/// The inverse of opening the container, it mirrors how each field is wrapped.
+pub fn create_fields_from_bindings(
+    fields: &Fields,
+    fail_safe: bool,
+) -> syn::Result<Punctuated<FieldValue, Token![,]>> {
+    try_map_fields_to_values(fields, |field, member| {
+        let options = FieldOptions::from_attributes(&field.attrs)?;
+        let binding = binding_ident(&member);
+
+        Ok(FieldValue {
+            attrs: vec![],
+            colon_token: Some(Default::default()),
+            expr: wrap_value(field, &options, fail_safe, parse_quote!(#binding)),
+            member,
+        })
+    })
+}
+
+// Wraps `value`, a value of the field's type, into the field's container
+fn wrap_value(field: &Field, options: &FieldOptions, fail_safe: bool, value: Expr) -> Expr {
+    let mut expr: Expr = if options.nested {
+        nested_value(&field.ty, value)
+    } else if options.fail_safe == Some(FailSafe::Elements) {
+        parse_quote!(empty_type::FallibleEach::from(#value))
+    } else if option_segment(field).is_some() {
+        parse_quote!(empty_type::Optional::from(#value))
+    } else {
+        parse_quote!(std::option::Option::Some(#value))
+    };
+
+    if fail_safe || options.fail_safe == Some(FailSafe::Value) {
+        expr = parse_quote!(empty_type::Fallible::from(#expr));
+    }
+
+    expr
+}
+
+/// Sets every field of a struct container from its environment variable.
+/// Nested containers read their fields under the name of the field holding them.
+pub fn create_env_fields(fields: &Fields, fail_safe: bool) -> syn::Result<Vec<TokenStream>> {
+    let mut set = vec![];
+    for (field, member) in fields.iter().zip(field_members(fields)) {
+        let options = FieldOptions::from_attributes(&field.attrs)?;
+        let name = env_name(&member);
+
+        match options.env {
+            Some(StrField::Skip) => {}
+            _ if options.nested => set.push(quote! {
+                empty_type::FromEnv::set_from_env(
+                    &mut self.#member,
+                    &format!("{}{}__", prefix, #name),
+                    env,
+                    errors,
+                );
+            }),
+            parser => {
+                // `Option` fields are set to `Some` of what their variable holds
+                let (ty, value): (Type, Expr) = match option_argument(field)? {
+                    Some(ty) => (ty.clone(), parse_quote!(std::option::Option::Some(value))),
+                    None => (field.ty.clone(), parse_quote!(value)),
+                };
+                let parse = match parser {
+                    Some(StrField::Serde) => quote!(deserialize),
+                    _ => quote!(parse),
+                };
+                let wrapped = wrap_value(field, &options, fail_safe, value);
+
+                set.push(quote! {
+                    if let Some(value) = env.#parse::<#ty>(&format!("{}{}", prefix, #name), errors) {
+                        self.#member = #wrapped;
+                    }
+                });
+            }
+        }
+    }
+
+    Ok(set)
+}
+
+/// Match arms setting every field of a struct container from a string by its
+/// serde name. A nested container takes the rest of the path.
+pub fn create_override_arms(
+    fields: &Fields,
+    fail_safe: bool,
+    rename_all: Option<&str>,
+) -> syn::Result<Vec<TokenStream>> {
+    let mut arms = vec![];
+    for (field, member) in fields.iter().zip(field_members(fields)) {
+        let options = FieldOptions::from_attributes(&field.attrs)?;
+        let name = serde_field_name(&field.attrs, &member, rename_all);
+
+        match options.overrides {
+            Some(StrField::Skip) => {}
+            _ if options.nested => arms.push(quote! {
+                (#name, Some(rest)) => empty_type::SetPath::set_path(&mut self.#member, rest, value)
+                    .map_err(|error| error.in_field(#name)),
+                (#name, None) => Err(empty_type::OverrideError::invalid(
+                    #name,
+                    "expected the path to one of its fields",
+                )),
+            }),
+            parser => {
+                let (ty, value): (Type, Expr) = match option_argument(field)? {
+                    Some(ty) => (ty.clone(), parse_quote!(std::option::Option::Some(value))),
+                    None => (field.ty.clone(), parse_quote!(value)),
+                };
+                let parse = match parser {
+                    Some(StrField::Serde) => quote!(deserialize_override),
+                    _ => quote!(parse_override),
+                };
+                let wrapped = wrap_value(field, &options, fail_safe, value);
+
+                arms.push(quote! {
+                    (#name, None) => {
+                        let value = empty_type::#parse::<#ty>(#name, value)?;
+                        self.#member = #wrapped;
+                        Ok(())
+                    }
+                });
+            }
+        }
+    }
+
+    Ok(arms)
+}
+
+// The variable of a field is its name in upper case, e.g. `MAX_CONNECTIONS`
+fn env_name(member: &Member) -> String {
+    match member {
+        Member::Named(ident) => ident.to_string().trim_start_matches("r#").to_uppercase(),
+        Member::Unnamed(index) => index.index.to_string(),
+    }
+}
+
+// The type held by an `Option` field, e.g. `T` for `Option<T>`
+fn option_argument(field: &Field) -> syn::Result<Option<&Type>> {
+    let segment = match option_segment(field) {
+        Some(segment) => segment,
+        None => return Ok(None),
+    };
+
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) => match args.args.first() {
+            Some(GenericArgument::Type(ty)) => Ok(Some(ty)),
+            _ => Err(Error::new_spanned(args, "expected a type argument")),
         },
+        _ => Err(Error::new_spanned(
+            &field.ty,
+            "expected `Option` to have a type argument, e.g. `Option<T>`",
+        )),
+    }
+}
+
+/// A field which fails to resolve when its container is unset. It's opened
+/// into `binding` before the value is built, so every failure can be collected.
+pub struct RequiredField {
+    pub binding: Ident,
+    pub opened: Expr,
+}
+
+pub fn create_unwraped_fields(
+    fields: &Fields,
+    access: FieldAccess,
+) -> syn::Result<(Vec<RequiredField>, Punctuated<FieldValue, Token![,]>)> {
+    let mut required = vec![];
+    let values = try_map_fields_to_values(fields, |field, member| {
+        let options = FieldOptions::from_attributes(&field.attrs)?;
+        let accessor = access.expr(&member);
+        let name = access.name(field, &member);
+        let output_type = match &field_default(field, &options) {
+            Some(default) => open_or_default(accessor, default),
+            None if options.fail_safe.is_some() => open_or_default(accessor, &FieldDefault::Trait),
+            None => {
+                let binding = prefixed_binding_ident("__field_", &member);
+                let expr = parse_quote!(#binding);
+                required.push(RequiredField {
+                    binding,
+                    opened: parse_quote! {
+                        empty_type::Container::try_open_with_meta(#accessor, #name)
+                    },
+                });
+                expr
+            }
+        };
+
+        Ok(FieldValue {
+            attrs: vec![],
+            colon_token: field.colon_token,
+            expr: output_type,
+            member,
+        })
+    })?;
+
+    Ok((required, values))
+}
+
+pub fn create_unwrapped_default_fields(
+    fields: &Fields,
+    access: FieldAccess,
+) -> syn::Result<Punctuated<FieldValue, Token![,]>> {
+    try_map_fields_to_values(fields, |field, member| {
+        let options = FieldOptions::from_attributes(&field.attrs)?;
+        let accessor = access.expr(&member);
+        let default = options.default.unwrap_or(FieldDefault::Trait);
+
+        Ok(FieldValue {
+            attrs: vec![],
+            colon_token: field.colon_token,
+            expr: open_or_default(accessor, &default),
+            member,
+        })
+    })
+}
+
+// What an unset field resolves to. `bool` fields are `false` unless they name
+// another default.
+fn field_default(field: &Field, options: &FieldOptions) -> Option<FieldDefault> {
+    match &options.default {
+        Some(default) => Some(default.clone()),
+        None if field_type_is_literally(field, "bool") => Some(FieldDefault::Trait),
+        None => None,
+    }
+}
+
+// Opens a field which resolves to a default instead of failing
+fn open_or_default(accessor: Expr, default: &FieldDefault) -> Expr {
+    match default {
+        FieldDefault::Trait => parse_quote! {
+            empty_type::Container::open_or_default(#accessor)
+        },
+        FieldDefault::Function(function) => parse_quote! {
+            empty_type::Container::open_or_else(#accessor, #function)
+        },
+        FieldDefault::Value(value) => parse_quote! {
+            empty_type::Container::open_or_else(#accessor, || #value)
+        },
+    }
+}
+
+pub fn field_members(fields: &Fields) -> Vec<Member> {
+    map_fields_to_values(fields, |_, member| FieldValue {
+        attrs: vec![],
+        colon_token: None,
+        expr: parse_quote!(()),
         member,
     })
+    .into_iter()
+    .map(|f| f.member)
+    .collect()
 }
 
 pub fn map_fields_to_values(
@@ -119,3 +755,26 @@ pub fn map_fields_to_values(
         Fields::Unit => Default::default(),
     }
 }
+
+pub fn try_map_fields_to_values(
+    fields: &Fields,
+    mut func: impl FnMut(&Field, Member) -> syn::Result<FieldValue>,
+) -> syn::Result<Punctuated<FieldValue, Token![,]>> {
+    match fields {
+        Fields::Named(named) => named
+            .named
+            .iter()
+            .map(|field| {
+                let ident = field.ident.clone().unwrap();
+                func(field, Member::Named(ident))
+            })
+            .collect(),
+        Fields::Unnamed(unnamed) => unnamed
+            .unnamed
+            .iter()
+            .enumerate()
+            .map(|(index, field)| func(field, Member::Unnamed(Index::from(index))))
+            .collect(),
+        Fields::Unit => Ok(Default::default()),
+    }
+}
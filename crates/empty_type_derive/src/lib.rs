@@ -19,55 +19,79 @@
 use proc_macro::TokenStream;
 
 use quote::quote;
-use syn::{parse_macro_input, DeriveInput, Fields};
+use syn::{parse_macro_input, DeriveInput, Error, Fields, Ident, Variant};
 
-use crate::attribute::find_path_of_attribute;
-use crate::type_information::TypeInformation;
+use crate::fields::{
+    bindings_pattern, create_diagnosed_fields, create_diffed_fields, create_env_fields,
+    create_field_patches, create_fields_from_bindings, create_merged_fields, create_missing_fields,
+    create_override_arms, create_set_fields, create_written_patches, field_members,
+    other_bindings_pattern, target_bindings_pattern, FieldAccess, RequiredField,
+};
+use crate::options::ContainerOptions;
+use crate::serde_names::serde_has;
+use crate::type_information::{TypeData, TypeInformation};
 
 mod fields;
+mod options;
+mod serde_names;
 mod type_information;
 
 #[proc_macro_derive(EmptyType, attributes(empty))]
 #[cfg_attr(docs_rs, doc(cfg(feature = "derive")))]
 #[doc = include_str!("../README.md")]
 pub fn empty_type(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
     create_struct_tokens(input)
-}
-
-#[derive(Default)]
-struct ContainerFlags {
-    fail_safe: bool,
-    deserialize: bool,
+        .unwrap_or_else(|error| error.to_compile_error())
+        .into()
 }
 
 mod attribute;
 
-fn create_struct_tokens(input: TokenStream) -> TokenStream {
-    let input = parse_macro_input!(input as DeriveInput);
-    let viz = input.vis.clone();
+fn create_struct_tokens(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
     // get the required bounds for serde destructuring '
-    let container_attributes = ContainerFlags {
-        fail_safe: find_path_of_attribute(&input.attrs, "fail_safe").is_some(),
-        deserialize: find_path_of_attribute(&input.attrs, "deserialize").is_some(),
-    };
+    let container_attributes = ContainerOptions::from_attributes(&input.attrs)?;
 
-    let type_information = crate::type_information::TypeInformation::new(input);
+    let type_information =
+        crate::type_information::TypeInformation::new(input, &container_attributes)?;
+    let viz = &type_information.derived_struct_visibility;
 
-    let output_impls = create_impl_for_output(&type_information, &container_attributes);
+    let output_impls = create_impl_for_output(&type_information, &container_attributes)?;
 
-    let end_punctuation = if matches!(&type_information.fields, &Fields::Unnamed(_)) {
-        Some(syn::token::Semi::default())
+    let input_impls = { Some(create_input_impls(&type_information)) };
+    let from_impl = create_impl_from_input(&type_information, &container_attributes)?;
+    let diff_impl = if container_attributes.diff {
+        Some(create_diff_impl(&type_information))
+    } else {
+        None
+    };
+    let env_impl = if container_attributes.env {
+        Some(create_env_impl(&type_information, &container_attributes)?)
+    } else {
+        None
+    };
+    let overrides_impl = if container_attributes.overrides {
+        Some(create_overrides_impl(
+            &type_information,
+            &container_attributes,
+        )?)
     } else {
         None
     };
-
-    let input_impls = { Some(create_input_impls(&type_information)) };
 
     #[cfg(feature = "serde")]
-    let derive = if container_attributes.deserialize {
-        quote! { #[derive(serde::Deserialize, Default )]}
-    } else {
-        quote! {#[derive(Default)]}
+    let derive = {
+        let deserialize = if container_attributes.deserialize {
+            Some(quote! { serde::Deserialize, })
+        } else {
+            None
+        };
+        let serialize = if container_attributes.serialize {
+            Some(quote! { serde::Serialize, })
+        } else {
+            None
+        };
+        quote! { #[derive(#deserialize #serialize Default )]}
     };
 
     #[cfg(not(feature = "serde"))]
@@ -77,25 +101,87 @@ fn create_struct_tokens(input: TokenStream) -> TokenStream {
     let attrs: Vec<syn::Attribute> = vec![];
 
     #[cfg(feature = "serde")]
-    let attrs: Vec<_> = if container_attributes.deserialize {
+    let attrs: Vec<_> = if container_attributes.serde() {
         type_information.only_serde_attributes()
     } else {
         vec![]
     };
 
+    let extra_derives = &container_attributes.derives;
+    let extra_derive = if extra_derives.is_empty() {
+        None
+    } else {
+        Some(quote! { #[derive(#(#extra_derives),*)] })
+    };
+    let extra_attrs = &container_attributes.attrs;
+
+    let docs = type_information.doc_attributes();
     let full_name = type_information.fully_qualified_derived_struct_name();
-    let fields = type_information.fields_wrapped_in_options(&container_attributes);
+
+    let definition = match &type_information.data {
+        TypeData::Struct(fields) => {
+            let end_punctuation = if matches!(fields, Fields::Unnamed(_) | Fields::Unit) {
+                Some(syn::token::Semi::default())
+            } else {
+                None
+            };
+            let fields =
+                TypeInformation::fields_wrapped_in_options(fields, &container_attributes, false)?;
+
+            quote! { #viz struct #full_name#fields#end_punctuation }
+        }
+        TypeData::Enum(variants) => {
+            let untagged = serde_has(&type_information.container_attributes, "untagged");
+            let variants = TypeInformation::variants_wrapped_in_options(
+                variants,
+                &container_attributes,
+                untagged,
+            )?;
+            let unselected = create_unselected_variant(&container_attributes);
+
+            quote! {
+                #viz enum #full_name {
+                    #unselected
+                    #(#variants),*
+                }
+            }
+        }
+    };
 
     let tokens = quote! {
+            #(#docs)*
             #derive
+            #extra_derive
             #(#attrs)*
-            #viz struct #full_name#fields#end_punctuation
+            #(#[#extra_attrs])*
+            #definition
 
             #input_impls
+            #from_impl
+            #diff_impl
+            #env_impl
+            #overrides_impl
             #output_impls
     };
 
-    tokens.into()
+    Ok(tokens)
+}
+
+// Enum containers start out without a variant. This variant is never
+// produced by deserialization, can't be serialized and fails to open.
+fn create_unselected_variant(container_flags: &ContainerOptions) -> proc_macro2::TokenStream {
+    let skip = if container_flags.serde() {
+        Some(quote! { #[serde(skip)] })
+    } else {
+        None
+    };
+
+    quote! {
+        #[default]
+        #[doc(hidden)]
+        #skip
+        ___Unselected,
+    }
 }
 
 fn create_input_impls(type_information: &TypeInformation) -> proc_macro2::TokenStream {
@@ -104,21 +190,533 @@ fn create_input_impls(type_information: &TypeInformation) -> proc_macro2::TokenS
     let full_maybe_name = type_information.fully_qualified_derived_struct_name();
     let where_clause = &type_information.where_clause;
 
+    let rename_all = type_information.rename_all();
+    let rename_all = rename_all.as_deref();
+    let field_names = match &type_information.data {
+        TypeData::Struct(fields) => fields
+            .iter()
+            .zip(field_members(fields))
+            .map(|(field, member)| FieldAccess::SelfMember(rename_all).name(field, &member))
+            .collect(),
+        TypeData::Enum(variants) => variants
+            .iter()
+            .flat_map(|variant| {
+                variant
+                    .fields
+                    .iter()
+                    .zip(field_members(&variant.fields))
+                    .map(move |(field, member)| {
+                        FieldAccess::Binding(variant, rename_all).name(field, &member)
+                    })
+            })
+            .collect::<Vec<_>>(),
+    };
+
     quote! {
         impl#prefix_generics empty_type::EmptyType for #full_known_name#where_clause {
             type Container = #full_maybe_name;
+
+            const FIELD_NAMES: &'static [&'static str] = &[#(#field_names),*];
+        }
+    }
+}
+
+// Converts a full value into a container with every field set
+fn create_impl_from_input(
+    type_information: &TypeInformation,
+    container_flags: &ContainerOptions,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let wrapped_name = &type_information.wrapped_struct_name;
+
+    let converted = match &type_information.data {
+        TypeData::Struct(fields) => {
+            let pattern = bindings_pattern(fields);
+            let fields = create_fields_from_bindings(fields, container_flags.fail_safe)?;
+            quote! {
+                let #wrapped_name #pattern = value;
+                Self { #fields }
+            }
+        }
+        TypeData::Enum(variants) => {
+            let arms = variants.iter().map(|variant| -> syn::Result<_> {
+                let variant_name = &variant.ident;
+                let pattern = bindings_pattern(&variant.fields);
+                let fields =
+                    create_fields_from_bindings(&variant.fields, container_flags.fail_safe)?;
+                Ok(quote! {
+                    #wrapped_name::#variant_name #pattern => Self::#variant_name { #fields },
+                })
+            });
+            let arms = arms.collect::<syn::Result<Vec<_>>>()?;
+            quote! {
+                match value {
+                    #(#arms)*
+                }
+            }
+        }
+    };
+
+    let prefix_generics = &type_information.prefix_generics;
+    let fully_qualified_derive_name = type_information.fully_qualified_derived_struct_name();
+    let fully_qualified_wrapped_name = type_information.fully_qualified_wrapped_struct_name();
+    let where_clause = &type_information.where_clause;
+
+    Ok(quote! {
+        impl#prefix_generics From<#fully_qualified_wrapped_name> for #fully_qualified_derive_name#where_clause {
+            fn from(value: #fully_qualified_wrapped_name) -> Self {
+                #converted
+            }
+        }
+    })
+}
+
+// Fields of the same variant are diffed one by one. A different variant
+// sets every field of the new one.
+fn create_diff_impl(type_information: &TypeInformation) -> proc_macro2::TokenStream {
+    let wrapped_name = &type_information.wrapped_struct_name;
+
+    let diffed = match &type_information.data {
+        TypeData::Struct(fields) => {
+            let pattern = bindings_pattern(fields);
+            let other_pattern = other_bindings_pattern(fields);
+            let fields = create_diffed_fields(fields);
+            quote! {
+                let #wrapped_name #pattern = old;
+                let #wrapped_name #other_pattern = new;
+                Self { #fields }
+            }
+        }
+        TypeData::Enum(variants) => {
+            let arms = variants.iter().map(|variant| {
+                let variant_name = &variant.ident;
+                let pattern = bindings_pattern(&variant.fields);
+                let other_pattern = other_bindings_pattern(&variant.fields);
+                let fields = create_diffed_fields(&variant.fields);
+                quote! {
+                    (#wrapped_name::#variant_name #pattern, #wrapped_name::#variant_name #other_pattern) => {
+                        Self::#variant_name { #fields }
+                    }
+                }
+            });
+            quote! {
+                match (old, new) {
+                    #(#arms)*
+                    #[allow(unreachable_patterns)]
+                    (_, new) => Self::from(std::clone::Clone::clone(new)),
+                }
+            }
+        }
+    };
+
+    let prefix_generics = &type_information.prefix_generics;
+    let fully_qualified_derive_name = type_information.fully_qualified_derived_struct_name();
+    let fully_qualified_wrapped_name = type_information.fully_qualified_wrapped_struct_name();
+    let where_clause = &type_information.where_clause;
+
+    quote! {
+        impl#prefix_generics empty_type::Diff for #fully_qualified_derive_name#where_clause {
+            fn diff(old: &#fully_qualified_wrapped_name, new: &#fully_qualified_wrapped_name) -> Self {
+                #diffed
+            }
         }
     }
 }
 
+// Only structs can be read from the environment, there's no variable to pick
+// the variant of an enum from
+fn create_env_impl(
+    type_information: &TypeInformation,
+    container_flags: &ContainerOptions,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let fields = match &type_information.data {
+        TypeData::Struct(fields) => create_env_fields(fields, container_flags.fail_safe)?,
+        TypeData::Enum(_) => {
+            return Err(Error::new_spanned(
+                &type_information.wrapped_struct_name,
+                "`env` can only be used on structs",
+            ))
+        }
+    };
+
+    let prefix_generics = &type_information.prefix_generics;
+    let fully_qualified_derive_name = type_information.fully_qualified_derived_struct_name();
+    let where_clause = &type_information.where_clause;
+
+    Ok(quote! {
+        impl#prefix_generics empty_type::FromEnv for #fully_qualified_derive_name#where_clause {
+            #[allow(unused_variables)]
+            fn set_from_env(
+                &mut self,
+                prefix: &str,
+                env: &empty_type::Env,
+                errors: &mut empty_type::EnvError,
+            ) {
+                #(#fields)*
+            }
+        }
+    })
+}
+
+// Fields are matched one segment of the path at a time. Like `env`, only
+// structs are supported as there's no path to a variant.
+fn create_overrides_impl(
+    type_information: &TypeInformation,
+    container_flags: &ContainerOptions,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let rename_all = type_information.rename_all();
+    let arms = match &type_information.data {
+        TypeData::Struct(fields) => {
+            create_override_arms(fields, container_flags.fail_safe, rename_all.as_deref())?
+        }
+        TypeData::Enum(_) => {
+            return Err(Error::new_spanned(
+                &type_information.wrapped_struct_name,
+                "`overrides` can only be used on structs",
+            ))
+        }
+    };
+
+    let prefix_generics = &type_information.prefix_generics;
+    let fully_qualified_derive_name = type_information.fully_qualified_derived_struct_name();
+    let where_clause = &type_information.where_clause;
+
+    Ok(quote! {
+        impl#prefix_generics empty_type::SetPath for #fully_qualified_derive_name#where_clause {
+            #[allow(unused_variables)]
+            fn set_path(
+                &mut self,
+                path: &str,
+                value: &str,
+            ) -> std::result::Result<(), empty_type::OverrideError> {
+                let (field, rest) = match path.split_once('.') {
+                    Some((field, rest)) => (field, Some(rest)),
+                    None => (path, None),
+                };
+
+                match (field, rest) {
+                    #(#arms)*
+                    _ => Err(empty_type::OverrideError::unknown_path(path)),
+                }
+            }
+        }
+    })
+}
+
 fn create_impl_for_output(
     type_information: &TypeInformation,
-    container_flags: &ContainerFlags,
+    container_flags: &ContainerOptions,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let rename_all = type_information.rename_all();
+    let rename_all = rename_all.as_deref();
+    let opened = match &type_information.data {
+        TypeData::Struct(fields) => {
+            create_struct_output(type_information, fields, container_flags)?
+        }
+        TypeData::Enum(variants) => {
+            create_enum_output(type_information, variants, container_flags)?
+        }
+    };
+
+    let unset = match &type_information.data {
+        TypeData::Struct(fields) => {
+            let members = field_members(fields);
+            quote! { true #(&& empty_type::Container::is_unset(&self.#members))* }
+        }
+        TypeData::Enum(_) => quote! { matches!(self, Self::___Unselected) },
+    };
+
+    let merged = match &type_information.data {
+        TypeData::Struct(fields) => {
+            let pattern = other_bindings_pattern(fields);
+            let merged = create_merged_fields(fields, FieldAccess::SelfMember(rename_all));
+            quote! {
+                let Self #pattern = other;
+                #(#merged;)*
+            }
+        }
+        TypeData::Enum(variants) => create_enum_merge(variants, rename_all),
+    };
+
+    // Every field is opened before anything is written, so a field which fails
+    // to open leaves the target untouched
+    let patched = match &type_information.data {
+        TypeData::Struct(fields) => {
+            let wrapped_name = &type_information.wrapped_struct_name;
+            let pattern = bindings_pattern(fields);
+            let target_pattern = target_bindings_pattern(fields);
+            let patches = create_field_patches(fields);
+            let written = create_written_patches(fields, FieldAccess::SelfMember(rename_all));
+            quote! {
+                let Self #pattern = self;
+                let #wrapped_name #target_pattern = target;
+                #(#patches;)*
+                Ok(empty_type::Patch::new(move |target: &mut <Self as empty_type::Container>::Value| {
+                    let mut changes = empty_type::Changes::none();
+                    let #wrapped_name #target_pattern = target;
+                    #(#written;)*
+                    changes
+                }))
+            }
+        }
+        TypeData::Enum(variants) => create_enum_patch(type_information, variants, rename_all),
+    };
+
+    let diagnosed = match &type_information.data {
+        TypeData::Struct(fields) => {
+            let pattern = bindings_pattern(fields);
+            let diagnosed = create_diagnosed_fields(fields, FieldAccess::SelfMember(rename_all));
+            quote! {
+                let Self #pattern = self;
+                #(#diagnosed;)*
+            }
+        }
+        TypeData::Enum(variants) => create_enum_diagnostics(variants, rename_all),
+    };
+
+    let set = match &type_information.data {
+        TypeData::Struct(fields) => {
+            let pattern = bindings_pattern(fields);
+            let set = create_set_fields(fields, FieldAccess::SelfMember(rename_all));
+            quote! {
+                let Self #pattern = self;
+                #(#set;)*
+            }
+        }
+        TypeData::Enum(variants) => create_enum_set_fields(variants, rename_all),
+    };
+
+    let missing = match &type_information.data {
+        TypeData::Struct(fields) => {
+            let pattern = bindings_pattern(fields);
+            let missing = create_missing_fields(fields, FieldAccess::SelfMember(rename_all))?;
+            quote! {
+                let Self #pattern = self;
+                #(#missing;)*
+            }
+        }
+        TypeData::Enum(variants) => {
+            create_enum_missing_fields(&type_information.wrapped_struct_name, variants, rename_all)?
+        }
+    };
+
+    let prefix_generics = &type_information.prefix_generics;
+    let fully_qualified_derive_name = type_information.fully_qualified_derived_struct_name();
+    let fully_qualified_wrapped_name = type_information.fully_qualified_wrapped_struct_name();
+    let where_clause = &type_information.where_clause;
+
+    Ok(quote! {
+        impl#prefix_generics empty_type::Container for #fully_qualified_derive_name#where_clause {
+            type Value = #fully_qualified_wrapped_name;
+
+            fn try_open(&mut self) -> Result<#fully_qualified_wrapped_name, empty_type::ResolveError> {
+                #opened
+            }
+
+            fn is_unset(&self) -> bool {
+                #unset
+            }
+
+            fn merge(&mut self, other: Self) {
+                #merged
+            }
+
+            fn try_patch<'__patch>(
+                self,
+                target: &#fully_qualified_wrapped_name,
+            ) -> Result<empty_type::Patch<'__patch, #fully_qualified_wrapped_name>, empty_type::ResolveError>
+            where
+                Self: '__patch,
+                #fully_qualified_wrapped_name: '__patch,
+            {
+                #patched
+            }
+
+            fn diagnostics(&self) -> Vec<empty_type::Diagnostic> {
+                let mut diagnostics = vec![];
+                #diagnosed
+                diagnostics
+            }
+
+            fn set_fields(&self) -> empty_type::FieldPaths {
+                let mut paths = empty_type::FieldPaths::none();
+                #set
+                paths
+            }
+
+            fn missing_fields(&self) -> empty_type::FieldPaths {
+                let mut paths = empty_type::FieldPaths::none();
+                #missing
+                paths
+            }
+        }
+    })
+}
+
+// An unselected variant in `other` keeps the container as is. The same
+// variant is merged field by field, a different variant replaces it.
+fn create_enum_merge(variants: &[Variant], rename_all: Option<&str>) -> proc_macro2::TokenStream {
+    let arms = variants.iter().map(|variant| {
+        let variant_name = &variant.ident;
+        let pattern = bindings_pattern(&variant.fields);
+        let other_pattern = other_bindings_pattern(&variant.fields);
+        let merged =
+            create_merged_fields(&variant.fields, FieldAccess::Binding(variant, rename_all));
+
+        quote! {
+            (Self::#variant_name #pattern, Self::#variant_name #other_pattern) => {
+                #(#merged;)*
+            }
+        }
+    });
+
+    quote! {
+        match (self, other) {
+            (_, Self::___Unselected) => {}
+            #(#arms)*
+            (this, other) => *this = other,
+        }
+    }
+}
+
+fn create_enum_diagnostics(
+    variants: &[Variant],
+    rename_all: Option<&str>,
+) -> proc_macro2::TokenStream {
+    let arms = variants.iter().map(|variant| {
+        let variant_name = &variant.ident;
+        let pattern = bindings_pattern(&variant.fields);
+        let diagnosed =
+            create_diagnosed_fields(&variant.fields, FieldAccess::Binding(variant, rename_all));
+
+        quote! {
+            Self::#variant_name #pattern => {
+                #(#diagnosed;)*
+            }
+        }
+    });
+
+    quote! {
+        match self {
+            Self::___Unselected => {}
+            #(#arms)*
+        }
+    }
+}
+
+// The fields of the selected variant are listed by name, as applying the
+// container to a value of the same variant writes them
+fn create_enum_set_fields(
+    variants: &[Variant],
+    rename_all: Option<&str>,
+) -> proc_macro2::TokenStream {
+    let arms = variants.iter().map(|variant| {
+        let variant_name = &variant.ident;
+        let pattern = bindings_pattern(&variant.fields);
+        let set = create_set_fields(&variant.fields, FieldAccess::Binding(variant, rename_all));
+
+        quote! {
+            Self::#variant_name #pattern => {
+                #(#set;)*
+            }
+        }
+    });
+
+    quote! {
+        match self {
+            Self::___Unselected => {}
+            #(#arms)*
+        }
+    }
+}
+
+// An enum without a selected variant is missing as a whole, it can't resolve
+// to any of them. It's listed under its own name when no field holds it.
+fn create_enum_missing_fields(
+    name: &Ident,
+    variants: &[Variant],
+    rename_all: Option<&str>,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let arms = variants.iter().map(|variant| -> syn::Result<_> {
+        let variant_name = &variant.ident;
+        let pattern = bindings_pattern(&variant.fields);
+        let missing =
+            create_missing_fields(&variant.fields, FieldAccess::Binding(variant, rename_all))?;
+
+        Ok(quote! {
+            Self::#variant_name #pattern => {
+                #(#missing;)*
+            }
+        })
+    });
+    let arms = arms.collect::<syn::Result<Vec<_>>>()?;
+
+    Ok(quote! {
+        match self {
+            Self::___Unselected => paths = empty_type::FieldPaths::named(stringify!(#name)),
+            #(#arms)*
+        }
+    })
+}
+
+// The same variant is patched field by field. A different variant has to
+// open completely and replaces the target.
+fn create_enum_patch(
+    type_information: &TypeInformation,
+    variants: &[Variant],
+    rename_all: Option<&str>,
 ) -> proc_macro2::TokenStream {
-    let field_unwrapping = if container_flags.fail_safe {
-        type_information.fields_uwnrapped_default()
+    let wrapped_name = &type_information.wrapped_struct_name;
+    let arms = variants.iter().map(|variant| {
+        let variant_name = &variant.ident;
+        let pattern = bindings_pattern(&variant.fields);
+        let target_pattern = target_bindings_pattern(&variant.fields);
+        let patches = create_field_patches(&variant.fields);
+        let written = create_written_patches(&variant.fields, FieldAccess::Binding(variant, rename_all));
+
+        quote! {
+            (Self::#variant_name #pattern, #wrapped_name::#variant_name #target_pattern) => {
+                #(#patches;)*
+                Ok(empty_type::Patch::new(move |target: &mut <Self as empty_type::Container>::Value| {
+                    let mut changes = empty_type::Changes::none();
+                    match target {
+                        #wrapped_name::#variant_name #target_pattern => {
+                            #(#written;)*
+                        }
+                        // the patch is written to the variant it was opened against
+                        #[allow(unreachable_patterns)]
+                        _ => {}
+                    }
+                    changes
+                }))
+            }
+        }
+    });
+
+    quote! {
+        match (self, target) {
+            (Self::___Unselected, _) => Ok(empty_type::Patch::none()),
+            #(#arms)*
+            #[allow(unreachable_patterns)]
+            (mut this, _) => Ok(empty_type::Patch::replace(
+                empty_type::Container::try_open(&mut this)?,
+            )),
+        }
+    }
+}
+
+fn create_struct_output(
+    type_information: &TypeInformation,
+    fields: &Fields,
+    container_flags: &ContainerOptions,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let rename_all = type_information.rename_all();
+    let rename_all = rename_all.as_deref();
+    let (required, field_unwrapping) = if container_flags.fail_safe {
+        let fields =
+            TypeInformation::fields_uwnrapped_default(fields, FieldAccess::SelfMember(rename_all))?;
+        (vec![], fields)
     } else {
-        type_information.fields_unwrapped()
+        TypeInformation::fields_unwrapped(fields, FieldAccess::SelfMember(rename_all))?
     };
 
     let field_unwrapping = if type_information.is_tuple_struct() {
@@ -128,19 +726,85 @@ fn create_impl_for_output(
         quote! { { #field_unwrapping } }
     };
 
-    let prefix_generics = &type_information.prefix_generics;
-    let fully_qualified_derive_name = type_information.fully_qualified_derived_struct_name();
-    let fully_qualified_wrapped_name = type_information.fully_qualified_wrapped_struct_name();
-    let where_clause = &type_information.where_clause;
     let wrapped_name = &type_information.wrapped_struct_name;
 
-    quote! {
-        impl#prefix_generics empty_type::Container for #fully_qualified_derive_name#where_clause {
-            type Value = #fully_qualified_wrapped_name;
+    Ok(create_checked_output(
+        &required,
+        container_flags,
+        quote! { #wrapped_name#field_unwrapping },
+    ))
+}
 
-            fn try_open(&mut self) -> Result<Self::Value, Box<dyn std::error::Error>> {
-                return Ok(#wrapped_name#field_unwrapping)
-            }
+fn create_enum_output(
+    type_information: &TypeInformation,
+    variants: &[Variant],
+    container_flags: &ContainerOptions,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let rename_all = type_information.rename_all();
+    let rename_all = rename_all.as_deref();
+    let wrapped_name = &type_information.wrapped_struct_name;
+    let unselected = wrapped_name.to_string();
+
+    let arms = variants.iter().map(|variant| -> syn::Result<_> {
+        let variant_name = &variant.ident;
+        let access = FieldAccess::Binding(variant, rename_all);
+        let pattern = bindings_pattern(&variant.fields);
+        let (required, mut field_unwrapping) = if container_flags.fail_safe {
+            let fields = TypeInformation::fields_uwnrapped_default(&variant.fields, access)?;
+            (vec![], fields)
+        } else {
+            TypeInformation::fields_unwrapped(&variant.fields, access)?
+        };
+        // Variants are always constructed with braces, `Variant { 0: value }`
+        field_unwrapping
+            .iter_mut()
+            .for_each(|f| f.colon_token = Some(Default::default()));
+
+        let output = create_checked_output(
+            &required,
+            container_flags,
+            quote! { #wrapped_name::#variant_name { #field_unwrapping } },
+        );
+
+        Ok(quote! {
+            Self::#variant_name #pattern => #output,
+        })
+    });
+    let arms = arms.collect::<syn::Result<Vec<_>>>()?;
+
+    Ok(quote! {
+        match self {
+            Self::___Unselected => Err(empty_type::ResolveError::Unselected { name: #unselected }),
+            #(#arms)*
         }
+    })
+}
+
+// Opens the required fields before `value` is built from them. Every failure
+// is collected into a single error unless the container fails fast.
+fn create_checked_output(
+    required: &[RequiredField],
+    container_flags: &ContainerOptions,
+    value: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let bindings: Vec<_> = required.iter().map(|field| &field.binding).collect();
+    let opened = required.iter().map(|field| &field.opened);
+
+    if required.is_empty() {
+        quote! { Ok(#value) }
+    } else if container_flags.fail_fast {
+        quote! {{
+            #(let #bindings = #opened?;)*
+            Ok(#value)
+        }}
+    } else {
+        quote! {{
+            let mut __errors = empty_type::FieldErrors::default();
+            #(let #bindings = __errors.check(#opened);)*
+            match (#(#bindings,)*) {
+                (#(Some(#bindings),)*) => Ok(#value),
+                _ => Err(__errors.into()),
+            }
+        }}
     }
 }
@@ -0,0 +1,379 @@
+/*
+ * Copyright [2022] [Kevin Velasco]
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::attribute::{option_entries, OptionEntry, OptionValue};
+use proc_macro2::TokenStream;
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+use syn::{Attribute, Error, Expr, ExprPath, Ident, Path, Token, Visibility};
+
+// Every option known to the derive and where it may be used
+const CONTAINER_OPTIONS: &[&str] = &[
+    "fail_safe",
+    "hint",
+    "deserialize",
+    "serialize",
+    "name",
+    "vis",
+    "derive",
+    "attr",
+    "diff",
+    "fail_fast",
+    "env",
+    "overrides",
+];
+const VARIANT_OPTIONS: &[&str] = &["attr"];
+const FIELD_OPTIONS: &[&str] = &[
+    "fail_safe",
+    "hint",
+    "nested",
+    "default",
+    "default_value",
+    "attr",
+    "env",
+    "overrides",
+];
+
+#[derive(Clone, Copy)]
+enum Level {
+    Container,
+    Variant,
+    Field,
+}
+
+impl Level {
+    fn options(self) -> &'static [&'static str] {
+        match self {
+            Level::Container => CONTAINER_OPTIONS,
+            Level::Variant => VARIANT_OPTIONS,
+            Level::Field => FIELD_OPTIONS,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Level::Container => "the type",
+            Level::Variant => "a variant",
+            Level::Field => "a field",
+        }
+    }
+}
+
+/// Options set on the type deriving `EmptyType`
+#[derive(Default)]
+pub struct ContainerOptions {
+    pub fail_safe: bool,
+    /// Deserialize fail-safe fields by the hint of their type
+    pub hint: bool,
+    pub deserialize: bool,
+    pub serialize: bool,
+    /// Implement `Diff` for the container
+    pub diff: bool,
+    /// Fail on the first field which can't be resolved
+    pub fail_fast: bool,
+    /// Implement `FromEnv` for the container
+    pub env: bool,
+    /// Implement `SetPath` for the container
+    pub overrides: bool,
+    pub name: Option<Ident>,
+    pub vis: Option<Visibility>,
+    /// Traits derived for the container on top of `Default`
+    pub derives: Vec<Path>,
+    /// Attributes applied to the container only, without the surrounding `#[]`
+    pub attrs: Vec<TokenStream>,
+}
+
+impl ContainerOptions {
+    pub fn from_attributes(attributes: &[Attribute]) -> syn::Result<Self> {
+        let mut options = Self::default();
+
+        for entry in option_entries(attributes)? {
+            match entry.key.to_string().as_str() {
+                "fail_safe" => options.fail_safe = entry.flag()?,
+                "hint" => options.hint = entry.flag()?,
+                "deserialize" => options.deserialize = entry.flag()?,
+                "serialize" => options.serialize = entry.flag()?,
+                "diff" => options.diff = entry.flag()?,
+                "fail_fast" => options.fail_fast = entry.flag()?,
+                "env" => options.env = entry.flag()?,
+                "overrides" => options.overrides = entry.flag()?,
+                "name" => {
+                    let name = entry.str_value()?;
+                    options.name = Some(name.parse().map_err(|_| {
+                        Error::new_spanned(name, "`name` should be a valid identifier")
+                    })?);
+                }
+                "vis" => {
+                    let vis = entry.str_value()?;
+                    options.vis = Some(vis.parse().map_err(|_| {
+                        Error::new_spanned(vis, "`vis` should be a visibility, e.g. `pub(crate)`")
+                    })?);
+                }
+                "derive" => {
+                    let derives = Punctuated::<Path, Token![,]>::parse_terminated
+                        .parse2(entry.list()?.clone())?;
+                    options.derives.extend(derives);
+                }
+                "attr" => options.attrs.push(attr_option(&entry)?),
+                _ => return Err(unknown_option(&entry, Level::Container)),
+            }
+        }
+
+        Ok(options)
+    }
+
+    // Whether serde attributes are forwarded to the container
+    pub fn serde(&self) -> bool {
+        self.deserialize || self.serialize
+    }
+}
+
+/// Options set on a single variant of an enum
+#[derive(Default)]
+pub struct VariantOptions {
+    pub attrs: Vec<TokenStream>,
+}
+
+impl VariantOptions {
+    pub fn from_attributes(attributes: &[Attribute]) -> syn::Result<Self> {
+        let mut options = Self::default();
+
+        for entry in option_entries(attributes)? {
+            match entry.key.to_string().as_str() {
+                "attr" => options.attrs.push(attr_option(&entry)?),
+                _ => return Err(unknown_option(&entry, Level::Variant)),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+/// Options set on a single field
+#[derive(Default)]
+pub struct FieldOptions {
+    pub fail_safe: Option<FailSafe>,
+    /// Deserialize the field by the hint of its type when it's fail-safe
+    pub hint: bool,
+    pub nested: bool,
+    pub default: Option<FieldDefault>,
+    pub env: Option<StrField>,
+    pub overrides: Option<StrField>,
+    pub attrs: Vec<TokenStream>,
+}
+
+/// How a field is read from a string, by `env` or `overrides`, when it isn't
+/// parsed with `FromStr`
+#[derive(Clone, Copy, PartialEq)]
+pub enum StrField {
+    /// e.g. `#[empty(env = "serde")]`, deserialized from the string
+    Serde,
+    /// e.g. `#[empty(env = "skip")]`, never read from a string
+    Skip,
+}
+
+/// What a fail-safe field falls back on when it fails to deserialize
+#[derive(Clone, Copy, PartialEq)]
+pub enum FailSafe {
+    /// `#[empty(fail_safe)]`, the whole value falls back to its default
+    Value,
+    /// `#[empty(fail_safe = "elements")]`, only the elements of a collection
+    /// which fail to deserialize are dropped
+    Elements,
+}
+
+/// What an unset field resolves to instead of failing
+#[derive(Clone)]
+pub enum FieldDefault {
+    /// `#[empty(default)]`, the type's `Default` impl
+    Trait,
+    /// `#[empty(default = "path::to::fn")]`
+    Function(ExprPath),
+    /// `#[empty(default_value = expr)]`
+    Value(Expr),
+}
+
+impl FieldOptions {
+    pub fn from_attributes(attributes: &[Attribute]) -> syn::Result<Self> {
+        let mut options = Self::default();
+
+        for entry in option_entries(attributes)? {
+            match entry.key.to_string().as_str() {
+                "fail_safe" => {
+                    let fail_safe = FailSafe::from_entry(&entry)?;
+                    if options.nested && fail_safe == FailSafe::Elements {
+                        return Err(nested_elements_error(&entry));
+                    }
+                    options.fail_safe = Some(fail_safe);
+                }
+                "hint" => options.hint = entry.flag()?,
+                "nested" => {
+                    options.nested = entry.flag()?;
+                    if options.fail_safe == Some(FailSafe::Elements) {
+                        return Err(nested_elements_error(&entry));
+                    }
+                }
+                "default" | "default_value" => {
+                    if options.default.is_some() {
+                        return Err(Error::new_spanned(
+                            &entry,
+                            "a field can only have one of `default` or `default_value`",
+                        ));
+                    }
+                    options.default = Some(FieldDefault::from_entry(&entry)?);
+                }
+                "env" => options.env = Some(StrField::from_entry(&entry)?),
+                "overrides" => options.overrides = Some(StrField::from_entry(&entry)?),
+                "attr" => options.attrs.push(attr_option(&entry)?),
+                _ => return Err(unknown_option(&entry, Level::Field)),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+impl FailSafe {
+    fn from_entry(entry: &OptionEntry) -> syn::Result<Self> {
+        if let OptionValue::Flag = entry.value {
+            return Ok(FailSafe::Value);
+        }
+
+        let mode = entry.str_value()?;
+        match mode.value().as_str() {
+            "elements" => Ok(FailSafe::Elements),
+            _ => Err(Error::new_spanned(
+                mode,
+                "`fail_safe` only accepts `\"elements\"`, e.g. `fail_safe = \"elements\"`",
+            )),
+        }
+    }
+}
+
+impl StrField {
+    fn from_entry(entry: &OptionEntry) -> syn::Result<Self> {
+        let mode = entry.str_value()?;
+        match mode.value().as_str() {
+            "serde" => Ok(StrField::Serde),
+            "skip" => Ok(StrField::Skip),
+            _ => Err(Error::new_spanned(
+                mode,
+                format!("`{}` should be one of `\"serde\"` or `\"skip\"`", entry.key),
+            )),
+        }
+    }
+}
+
+// A nested container is fail-safe field by field, it has no elements to drop
+fn nested_elements_error(entry: &OptionEntry) -> Error {
+    Error::new_spanned(
+        entry,
+        "`fail_safe = \"elements\"` can't be used on a `nested` field",
+    )
+}
+
+impl FieldDefault {
+    fn from_entry(entry: &OptionEntry) -> syn::Result<Self> {
+        if entry.key == "default_value" {
+            return Ok(FieldDefault::Value(entry.value()?.clone()));
+        }
+
+        match entry.value {
+            OptionValue::Flag => Ok(FieldDefault::Trait),
+            _ => {
+                let path = entry.str_value()?;
+                path.parse().map(FieldDefault::Function).map_err(|_| {
+                    Error::new_spanned(path, "`default` should be a path to a function")
+                })
+            }
+        }
+    }
+}
+
+// `attr(...)` holds the contents of an attribute for the container
+fn attr_option(entry: &OptionEntry) -> syn::Result<TokenStream> {
+    let attr = entry.list()?;
+    if attr.is_empty() {
+        return Err(Error::new_spanned(
+            entry,
+            "`attr` should contain an attribute, e.g. `attr(allow(dead_code))`",
+        ));
+    }
+
+    Ok(attr.clone())
+}
+
+fn unknown_option(entry: &OptionEntry, level: Level) -> Error {
+    let key = entry.key.to_string();
+
+    let elsewhere: Vec<_> = [Level::Container, Level::Variant, Level::Field]
+        .iter()
+        .filter(|other| other.options().contains(&key.as_str()))
+        .map(|other| other.name())
+        .collect();
+    if !elsewhere.is_empty() {
+        return Error::new_spanned(
+            &entry.key,
+            format!(
+                "`{}` can only be used on {}, not on {}",
+                key,
+                elsewhere.join(" or "),
+                level.name()
+            ),
+        );
+    }
+
+    // short names are only suggested for close typos
+    let suggestion = level
+        .options()
+        .iter()
+        .map(|option| (edit_distance(&key, option), option))
+        .filter(|(distance, _)| *distance <= key.len() / 3 + 1)
+        .min_by_key(|(distance, _)| *distance);
+
+    let message = match suggestion {
+        Some((_, option)) => format!("unknown option `{}`, did you mean `{}`?", key, option),
+        None => format!(
+            "unknown option `{}`, expected one of {}",
+            key,
+            level
+                .options()
+                .iter()
+                .map(|option| format!("`{}`", option))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    };
+
+    Error::new_spanned(&entry.key, message)
+}
+
+// Levenshtein distance between two option names
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a != *b);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
@@ -0,0 +1,167 @@
+/*
+ * Copyright [2022] [Kevin Velasco]
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::attribute::{OptionEntry, OptionValue};
+use syn::punctuated::Punctuated;
+use syn::{Attribute, Member, Token, Variant};
+
+/// The name a field is deserialized from. Follows `#[serde(rename)]` on the
+/// field and `#[serde(rename_all)]` on its container, `rename_all` being the
+/// rule found by [`rename_all`].
+pub fn serde_field_name(attrs: &[Attribute], member: &Member, rename_all: Option<&str>) -> String {
+    if let Some(name) = serde_rename(attrs, "rename") {
+        return name;
+    }
+
+    let name = match member {
+        Member::Named(ident) => ident.to_string().trim_start_matches("r#").to_owned(),
+        Member::Unnamed(index) => return index.index.to_string(),
+    };
+
+    match rename_all {
+        Some(rule) => apply_rename_rule(rule, &name),
+        None => name,
+    }
+}
+
+/// The name a variant is deserialized from. Follows `#[serde(rename)]` on the
+/// variant and `#[serde(rename_all)]` on its enum.
+pub fn serde_variant_name(variant: &Variant, rename_all: Option<&str>) -> String {
+    if let Some(name) = serde_rename(&variant.attrs, "rename") {
+        return name;
+    }
+
+    let name = variant
+        .ident
+        .to_string()
+        .trim_start_matches("r#")
+        .to_owned();
+    match rename_all {
+        Some(rule) => apply_variant_rename_rule(rule, &name),
+        None => name,
+    }
+}
+
+/// The rule of `#[serde(rename_all = "...")]` on a container
+pub fn rename_all(attrs: &[Attribute]) -> Option<String> {
+    serde_rename(attrs, "rename_all")
+}
+
+/// Whether the `#[serde(...)]` attributes hold `key`, e.g. `untagged` or `default`
+pub fn serde_has(attrs: &[Attribute], key: &str) -> bool {
+    serde_entries(attrs).iter().any(|entry| entry.key == key)
+}
+
+// The deserialized name of `rename = "..."` or `rename(deserialize = "...")`.
+// Malformed attributes are left for serde to report.
+fn serde_rename(attrs: &[Attribute], key: &str) -> Option<String> {
+    serde_entries(attrs)
+        .into_iter()
+        .filter(|entry| entry.key == key)
+        .find_map(|entry| match &entry.value {
+            OptionValue::Value(..) => entry.str_value().ok().map(|name| name.value()),
+            OptionValue::List(_, content) => syn::parse::Parser::parse2(
+                Punctuated::<OptionEntry, Token![,]>::parse_terminated,
+                content.clone(),
+            )
+            .ok()?
+            .into_iter()
+            .find(|entry| entry.key == "deserialize")?
+            .str_value()
+            .ok()
+            .map(|name| name.value()),
+            OptionValue::Flag => None,
+        })
+}
+
+fn serde_entries(attrs: &[Attribute]) -> Vec<OptionEntry> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("serde"))
+        .filter_map(|attr| {
+            attr.parse_args_with(Punctuated::<OptionEntry, Token![,]>::parse_terminated)
+                .ok()
+        })
+        .flatten()
+        .collect()
+}
+
+// Mirrors how serde renames a snake_case field. Unknown rules are left for
+// serde to report.
+fn apply_rename_rule(rule: &str, field: &str) -> String {
+    match rule {
+        "UPPERCASE" | "SCREAMING_SNAKE_CASE" => field.to_ascii_uppercase(),
+        "PascalCase" => pascal_case(field),
+        "camelCase" => {
+            let pascal = pascal_case(field);
+            let mut chars = pascal.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_lowercase().to_string() + chars.as_str(),
+                None => pascal,
+            }
+        }
+        "kebab-case" => field.replace('_', "-"),
+        "SCREAMING-KEBAB-CASE" => field.to_ascii_uppercase().replace('_', "-"),
+        _ => field.to_owned(),
+    }
+}
+
+// Mirrors how serde renames a PascalCase variant
+fn apply_variant_rename_rule(rule: &str, variant: &str) -> String {
+    match rule {
+        "lowercase" => variant.to_ascii_lowercase(),
+        "UPPERCASE" => variant.to_ascii_uppercase(),
+        "camelCase" => {
+            let mut chars = variant.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_lowercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        }
+        "snake_case" => snake_case(variant),
+        "SCREAMING_SNAKE_CASE" => snake_case(variant).to_ascii_uppercase(),
+        "kebab-case" => snake_case(variant).replace('_', "-"),
+        "SCREAMING-KEBAB-CASE" => snake_case(variant).to_ascii_uppercase().replace('_', "-"),
+        _ => variant.to_owned(),
+    }
+}
+
+fn snake_case(variant: &str) -> String {
+    let mut snake = String::new();
+    for (i, ch) in variant.char_indices() {
+        if i > 0 && ch.is_uppercase() {
+            snake.push('_');
+        }
+        snake.push(ch.to_ascii_lowercase());
+    }
+    snake
+}
+
+fn pascal_case(field: &str) -> String {
+    let mut pascal = String::new();
+    let mut capitalize = true;
+    for ch in field.chars() {
+        if ch == '_' {
+            capitalize = true;
+        } else if capitalize {
+            pascal.push(ch.to_ascii_uppercase());
+            capitalize = false;
+        } else {
+            pascal.push(ch);
+        }
+    }
+    pascal
+}
@@ -15,27 +15,40 @@
  */
 
 use crate::attribute::filter_attrs_by_own;
-use crate::fields::{create_unwraped_fields, create_unwrapped_default_fields};
-use crate::{find_path_of_attribute, ContainerFlags};
+use crate::fields::{
+    create_unwraped_fields, create_unwrapped_default_fields, FieldAccess, RequiredField,
+};
+use crate::options::{ContainerOptions, FailSafe, FieldOptions, VariantOptions};
+use crate::serde_names::serde_has;
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::{format_ident, quote};
+use syn::parse_quote;
 use syn::punctuated::Punctuated;
 use syn::{
-    Attribute, Data, DeriveInput, FieldValue, Fields, GenericParam, Generics, Token, TypeParam,
-    WhereClause,
+    Attribute, Data, DeriveInput, Error, Field, FieldValue, Fields, GenericParam, Generics, Token,
+    TypeParam, Variant, Visibility, WhereClause,
 };
 
 pub struct TypeInformation {
     pub wrapped_struct_name: Ident,
+    pub derived_struct_name: Ident,
+    pub derived_struct_visibility: Visibility,
     pub prefix_generics: Generics,
     pub postfix_generics: Generics,
     pub where_clause: Option<WhereClause>,
-    pub fields: Fields,
+    pub data: TypeData,
     pub container_attributes: Vec<Attribute>,
 }
 
+// The members of the source type. Structs produce a single set of fields,
+// enums produce a set of fields per variant
+pub enum TypeData {
+    Struct(Fields),
+    Enum(Vec<Variant>),
+}
+
 impl TypeInformation {
-    pub fn new(input: DeriveInput) -> Self {
+    pub fn new(input: DeriveInput, options: &ContainerOptions) -> syn::Result<Self> {
         // Prefix generics contain type definitions and type bounds but no where clauses.
         // Found in impl blocks like `impl<'a, T: 'a>
         let prefix_generics = {
@@ -45,16 +58,24 @@ impl TypeInformation {
         };
 
         let postfix_generics = Self::create_postfix_generics(input.generics.clone());
-        let fields = Self::extract_fields(input.data);
+        let data = Self::extract_data(input.data)?;
+        let derived_struct_name = match &options.name {
+            Some(name) => name.clone(),
+            None => format_ident!("___Empty{}", input.ident),
+        };
+        // Visibility of the container. Defaults to the visibility of the source type
+        let derived_struct_visibility = options.vis.clone().unwrap_or(input.vis);
 
-        Self {
+        Ok(Self {
             prefix_generics,
             postfix_generics,
             wrapped_struct_name: input.ident,
-            fields,
+            derived_struct_name,
+            derived_struct_visibility,
+            data,
             where_clause: input.generics.where_clause,
             container_attributes: input.attrs,
-        }
+        })
     }
 
     #[cfg(feature = "serde")]
@@ -72,92 +93,199 @@ impl TypeInformation {
             .collect()
     }
 
-    fn extract_fields(data: Data) -> Fields {
+    /// The `#[serde(rename_all)]` rule fields, or variants of an enum, are named by
+    pub fn rename_all(&self) -> Option<String> {
+        crate::serde_names::rename_all(&self.container_attributes)
+    }
+
+    /// Doc comments of the source type, forwarded to the container
+    pub fn doc_attributes(&self) -> Vec<Attribute> {
+        only_doc_attributes(&self.container_attributes)
+    }
+
+    fn extract_data(data: Data) -> syn::Result<TypeData> {
         match data {
-            Data::Struct(str) => str.fields,
-            Data::Enum(_) => {
-                panic!("")
-            }
-            Data::Union(_) => {
-                panic!("")
-            }
+            Data::Struct(str) => Ok(TypeData::Struct(str.fields)),
+            Data::Enum(en) => Ok(TypeData::Enum(en.variants.into_iter().collect())),
+            Data::Union(un) => Err(Error::new_spanned(
+                un.union_token,
+                "EmptyType can't be derived for unions",
+            )),
         }
     }
 
-    pub fn fields_unwrapped(&self) -> Punctuated<FieldValue, Token![,]> {
-        create_unwraped_fields(&self.fields)
+    pub fn fields_unwrapped(
+        fields: &Fields,
+        access: FieldAccess,
+    ) -> syn::Result<(Vec<RequiredField>, Punctuated<FieldValue, Token![,]>)> {
+        create_unwraped_fields(fields, access)
     }
 
-    pub fn fields_uwnrapped_default(&self) -> Punctuated<FieldValue, Token![,]> {
-        create_unwrapped_default_fields(&self.fields)
+    pub fn fields_uwnrapped_default(
+        fields: &Fields,
+        access: FieldAccess,
+    ) -> syn::Result<Punctuated<FieldValue, Token![,]>> {
+        create_unwrapped_default_fields(fields, access)
     }
 
+    // Fields of untagged variants are `required`: serde tells the variants apart
+    // by the fields they hold, so a missing field has to fail the variant rather
+    // than default to an unset container.
     pub(crate) fn fields_wrapped_in_options(
-        &self,
-        container_attributes: &ContainerFlags,
-    ) -> Fields {
-        let mut fields = self.fields.clone();
+        fields: &Fields,
+        container_attributes: &ContainerOptions,
+        required: bool,
+    ) -> syn::Result<Fields> {
+        let mut fields = fields.clone();
         match &mut fields {
-            Fields::Named(named_field) => named_field.named.iter_mut().for_each(|f| {
-                crate::fields::wrap_field_in_option(f);
+            Fields::Named(named_field) => named_field
+                .named
+                .iter_mut()
+                .try_for_each(|f| Self::wrap_field(f, container_attributes, required))?,
+            Fields::Unnamed(unnamed_field) => unnamed_field
+                .unnamed
+                .iter_mut()
+                .try_for_each(|f| Self::wrap_field(f, container_attributes, required))?,
+            Fields::Unit => {}
+        }
 
-                if container_attributes.fail_safe
-                    || find_path_of_attribute(&f.attrs, "fail_safe").is_some()
-                {
-                    crate::fields::wrap_option_in_fallable(f);
-                }
+        Ok(fields)
+    }
 
-                // filter the field's attributes.
-                f.attrs = std::mem::take(&mut f.attrs)
-                    .into_iter()
-                    .filter(|f| !filter_attrs_by_own(f))
-                    .collect();
-                if !container_attributes.deserialize {
-                    f.attrs.clear()
-                }
-            }),
-            Fields::Unnamed(unnamed_field) => unnamed_field.unnamed.iter_mut().for_each(|f| {
-                crate::fields::wrap_field_in_option(f);
-
-                if container_attributes.fail_safe
-                    || find_path_of_attribute(&f.attrs, "fail_safe").is_some()
-                {
-                    crate::fields::wrap_option_in_fallable(f);
-                }
+    fn wrap_field(
+        f: &mut Field,
+        container_attributes: &ContainerOptions,
+        required: bool,
+    ) -> syn::Result<()> {
+        let options = FieldOptions::from_attributes(&f.attrs)?;
+        // a missing `Option` is `None` to serde, untagged or not
+        let required = required && crate::fields::option_segment(f).is_none();
+        let fail_safe = container_attributes.fail_safe || options.fail_safe.is_some();
+        if options.hint && !fail_safe {
+            return Err(Error::new_spanned(
+                &*f,
+                "`hint` only applies to fail-safe fields, e.g. `#[empty(fail_safe, hint)]`",
+            ));
+        }
+
+        if options.nested {
+            crate::fields::wrap_field_in_empty(f);
+        } else if options.fail_safe == Some(FailSafe::Elements) {
+            crate::fields::wrap_field_in_fallible_each(f);
+        } else {
+            crate::fields::wrap_field_in_option(f)?;
+        }
 
-                // filter the field's attributes.
-                f.attrs = std::mem::take(&mut f.attrs)
+        if container_attributes.fail_safe || options.fail_safe == Some(FailSafe::Value) {
+            crate::fields::wrap_option_in_fallable(f);
+        }
+
+        // filter the field's attributes.
+        f.attrs = std::mem::take(&mut f.attrs)
+            .into_iter()
+            .filter(|f| !filter_attrs_by_own(f))
+            .collect();
+
+        if !container_attributes.serde() {
+            f.attrs = only_doc_attributes(&f.attrs);
+        }
+
+        f.attrs
+            .extend(options.attrs.iter().map(|attr| parse_quote!(#[#attr])));
+
+        // the field's own serde attributes win over the ones added here, serde
+        // rejects an attribute given twice
+        let has_default = serde_has(&f.attrs, "default");
+        let reads_with = serde_has(&f.attrs, "deserialize_with") || serde_has(&f.attrs, "with");
+        let skips = serde_has(&f.attrs, "skip_serializing_if");
+
+        if container_attributes.deserialize && !required && !has_default {
+            // a missing field is an unset container, not an error
+            f.attrs.push(parse_quote!(#[serde(default)]));
+        }
+
+        if container_attributes.deserialize
+            && required
+            && !options.nested
+            && !fail_safe
+            && !reads_with
+        {
+            // serde reads a missing `Option` as `None` unless it's read with a function
+            f.attrs.push(parse_quote!(
+                #[serde(deserialize_with = "serde::Deserialize::deserialize")]
+            ));
+        }
+
+        if container_attributes.deserialize
+            && fail_safe
+            && (container_attributes.hint || options.hint)
+            && !reads_with
+        {
+            // formats which aren't self-describing reject `deserialize_any`
+            f.attrs.push(parse_quote!(
+                #[serde(deserialize_with = "empty_type::deserialize_hinted")]
+            ));
+        }
+
+        if container_attributes.serialize && f.ident.is_some() && !skips {
+            // unset fields are left out rather than written as `null`. Positional
+            // fields can't be left out without shifting the fields after them.
+            f.attrs.push(parse_quote!(
+                #[serde(skip_serializing_if = "empty_type::Container::is_unset")]
+            ));
+        }
+
+        Ok(())
+    }
+
+    // Variants of the container mirror the variants of the source enum with their
+    // fields wrapped. Discriminants are dropped as the container can't honor them.
+    pub(crate) fn variants_wrapped_in_options(
+        variants: &[Variant],
+        container_attributes: &ContainerOptions,
+        untagged: bool,
+    ) -> syn::Result<Vec<Variant>> {
+        variants
+            .iter()
+            .map(|variant| {
+                let options = VariantOptions::from_attributes(&variant.attrs)?;
+                let untagged = untagged || serde_has(&variant.attrs, "untagged");
+                let mut variant = variant.clone();
+                variant.fields = Self::fields_wrapped_in_options(
+                    &variant.fields,
+                    container_attributes,
+                    untagged,
+                )?;
+                variant.discriminant = None;
+                variant.attrs = std::mem::take(&mut variant.attrs)
                     .into_iter()
-                    .filter(|f| !filter_attrs_by_own(f))
+                    .filter(|a| !filter_attrs_by_own(a))
                     .collect();
 
-                if !container_attributes.deserialize {
-                    f.attrs.clear()
+                if !container_attributes.serde() {
+                    variant.attrs = only_doc_attributes(&variant.attrs)
                 }
-            }),
-            Fields::Unit => {}
-        }
-
-        fields
+                variant
+                    .attrs
+                    .extend(options.attrs.iter().map(|attr| parse_quote!(#[#attr])));
+                Ok(variant)
+            })
+            .collect()
     }
 
     pub fn is_tuple_struct(&self) -> bool {
-        matches!(&self.fields, &Fields::Unnamed(_))
+        matches!(&self.data, TypeData::Struct(Fields::Unnamed(_)))
     }
 
     pub fn fully_qualified_wrapped_struct_name(&self) -> TokenStream {
-        let ref postfix_generics = self.postfix_generics;
+        let postfix_generics = &self.postfix_generics;
         let name = &self.wrapped_struct_name;
         quote! { #name#postfix_generics }
     }
 
-    pub fn derived_struct_name(&self) -> Ident {
-        format_ident!("___Empty{}", self.wrapped_struct_name)
-    }
-
     pub fn fully_qualified_derived_struct_name(&self) -> TokenStream {
-        let ref postfix_generics = self.postfix_generics;
-        let name = self.derived_struct_name();
+        let postfix_generics = &self.postfix_generics;
+        let name = &self.derived_struct_name;
         quote! { #name#postfix_generics }
     }
 
@@ -191,3 +319,11 @@ impl TypeInformation {
         generics
     }
 }
+
+fn only_doc_attributes(attrs: &[Attribute]) -> Vec<Attribute> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("doc"))
+        .cloned()
+        .collect()
+}
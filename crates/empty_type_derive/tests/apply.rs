@@ -0,0 +1,177 @@
+/*
+ * Copyright [2022] [Kevin Velasco]
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use empty_type::{Changes, EmptyType};
+use empty_type_derive::EmptyType;
+
+#[derive(EmptyType, Debug, Clone, PartialEq)]
+struct Resource {
+    name: String,
+    description: Option<String>,
+    archived: bool,
+    #[empty(nested)]
+    owner: Owner,
+    #[empty(nested)]
+    visibility: Visibility,
+}
+
+#[derive(EmptyType, Debug, Clone, PartialEq)]
+struct Owner {
+    id: u64,
+    email: String,
+}
+
+#[derive(EmptyType, Debug, Clone, PartialEq)]
+enum Visibility {
+    Public,
+    Team { id: u64, editable: bool },
+}
+
+#[derive(EmptyType)]
+struct Marker;
+
+fn resource() -> Resource {
+    Resource {
+        name: "service".to_owned(),
+        description: Some("a service".to_owned()),
+        archived: false,
+        owner: Owner {
+            id: 1,
+            email: "owner@example.com".to_owned(),
+        },
+        visibility: Visibility::Team {
+            id: 7,
+            editable: false,
+        },
+    }
+}
+
+#[test]
+fn only_set_fields_are_written() {
+    let mut patch = Resource::new_empty();
+    patch.name = Some("renamed".to_owned());
+
+    let mut target = resource();
+    let changes = patch.apply_to(&mut target).unwrap();
+
+    assert_eq!(target.name, "renamed");
+    assert_eq!(target.owner, resource().owner);
+    assert_eq!(changes.iter().collect::<Vec<_>>(), vec!["name"]);
+}
+
+#[test]
+fn nested_containers_are_applied_field_by_field() {
+    let mut patch = Resource::new_empty();
+    patch.owner.email = Some("new@example.com".to_owned());
+
+    let mut target = resource();
+    let changes = patch.apply_to(&mut target).unwrap();
+
+    assert_eq!(target.owner.id, 1);
+    assert_eq!(target.owner.email, "new@example.com");
+    assert!(changes.contains("owner.email"));
+    assert_eq!(changes.len(), 1);
+}
+
+#[test]
+fn optional_fields_can_be_cleared() {
+    let mut patch = Resource::new_empty();
+    patch.description = None.into();
+
+    let mut target = resource();
+    let changes = patch.apply_to(&mut target).unwrap();
+
+    assert_eq!(target.description, None);
+    assert!(changes.contains("description"));
+}
+
+#[test]
+fn empty_patches_change_nothing() {
+    let mut target = resource();
+    let changes = Resource::new_empty().apply_to(&mut target).unwrap();
+
+    assert_eq!(target, resource());
+    assert!(changes.is_empty());
+}
+
+#[test]
+fn enum_fields_are_applied_to_the_same_variant() {
+    let mut patch = Resource::new_empty();
+    *patch.visibility = ___EmptyVisibility::Team {
+        id: None,
+        editable: Some(true),
+    };
+
+    let mut target = resource();
+    let changes = patch.apply_to(&mut target).unwrap();
+
+    let visibility = Visibility::Team {
+        id: 7,
+        editable: true,
+    };
+    assert_eq!(target.visibility, visibility);
+    assert!(changes.contains("visibility.Team::editable"));
+}
+
+#[test]
+fn enum_fields_switching_variants_are_replaced() {
+    let mut patch = Resource::new_empty();
+    *patch.visibility = ___EmptyVisibility::Public;
+
+    let mut target = resource();
+    let changes = patch.apply_to(&mut target).unwrap();
+
+    assert_eq!(target.visibility, Visibility::Public);
+    assert!(changes.contains("visibility"));
+}
+
+#[test]
+fn switching_to_an_incomplete_variant_fails() {
+    let mut patch = Visibility::new_empty();
+    *patch = ___EmptyVisibility::Team {
+        id: None,
+        editable: Some(true),
+    };
+
+    let mut target = Visibility::Public;
+    assert!(patch.apply_to(&mut target).is_err());
+    assert_eq!(target, Visibility::Public);
+}
+
+#[test]
+fn failing_to_apply_leaves_the_target_untouched() {
+    let mut patch = Resource::new_empty();
+    patch.name = Some("new".to_owned());
+    patch.archived = Some(true);
+    *patch.visibility = ___EmptyVisibility::Team {
+        id: None,
+        editable: Some(true),
+    };
+
+    let mut target = resource();
+    target.visibility = Visibility::Public;
+    let original = target.clone();
+
+    assert!(patch.apply_to(&mut target).is_err());
+    assert_eq!(target, original);
+}
+
+#[test]
+fn unit_structs_can_be_applied() {
+    let changes = Marker::new_empty().apply_to(&mut Marker).unwrap();
+
+    assert_eq!(changes, Changes::none());
+}
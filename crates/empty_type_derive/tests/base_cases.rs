@@ -41,3 +41,14 @@ fn empty_type_can_be_unwrapped() {
     let unwrapped = empty.resolve();
     assert!(unwrapped.value.is_some());
 }
+
+#[test]
+fn tuple_struct_can_be_unwrapped() {
+    let mut container = Data::new_container();
+    container.0 = Some(String::from("value"));
+    container.1 = Some(1);
+
+    let unwrapped = empty_type::Container::open(&mut container);
+    assert_eq!(unwrapped.0, "value");
+    assert_eq!(unwrapped.1, 1);
+}
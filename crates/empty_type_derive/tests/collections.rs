@@ -0,0 +1,214 @@
+/*
+ * Copyright [2022] [Kevin Velasco]
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use empty_type::{Container, Empty, EmptyType, Field};
+use empty_type_derive::EmptyType;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::rc::Rc;
+use std::sync::Arc;
+
+#[derive(EmptyType, Debug, PartialEq, Clone)]
+struct Replica {
+    host: String,
+    port: u16,
+}
+
+fn replica(host: &str, port: Option<u16>) -> Empty<Replica> {
+    let mut empty = Replica::new_empty();
+    empty.host = Some(host.to_owned());
+    empty.port = port;
+    empty
+}
+
+fn resolved(host: &str, port: u16) -> Replica {
+    Replica {
+        host: host.to_owned(),
+        port,
+    }
+}
+
+#[test]
+fn sequences_open_element_by_element() {
+    let mut replicas = vec![replica("a", Some(1)), replica("b", Some(2))];
+    assert_eq!(replicas.open(), vec![resolved("a", 1), resolved("b", 2)]);
+
+    let mut replicas: VecDeque<_> = vec![replica("a", Some(1))].into();
+    assert_eq!(replicas.open(), VecDeque::from(vec![resolved("a", 1)]));
+}
+
+#[test]
+fn element_failures_are_attributed_to_their_index() {
+    let mut replicas = vec![
+        replica("a", Some(1)),
+        replica("b", None),
+        replica("c", None),
+    ];
+
+    let error = replicas.try_open().unwrap_err();
+    let paths: Vec<_> = error
+        .failures()
+        .into_iter()
+        .map(|(path, _)| path.to_string())
+        .collect();
+
+    assert_eq!(paths, vec!["[1].port", "[2].port"]);
+    assert_eq!(
+        error.path().segments(),
+        &[Field::Index(1), Field::Named("port")]
+    );
+}
+
+#[test]
+fn map_failures_are_attributed_to_their_key() {
+    let mut servers = BTreeMap::new();
+    servers.insert("eu".to_owned(), replica("eu", None));
+    servers.insert("us".to_owned(), replica("us", Some(2)));
+
+    let error = servers.try_open().unwrap_err();
+
+    assert_eq!(error.path().to_string(), "[\"eu\"].port");
+    assert_eq!(
+        error.to_string(),
+        "Failed to resolve field `[\"eu\"].port`. Opened to `None` value"
+    );
+}
+
+#[test]
+fn maps_merge_entry_by_entry() {
+    let mut lower = HashMap::new();
+    lower.insert("eu", replica("eu", Some(1)));
+    lower.insert("us", replica("us", Some(2)));
+
+    let mut higher = HashMap::new();
+    let mut eu = Replica::new_empty();
+    eu.port = Some(10);
+    higher.insert("eu", eu);
+    higher.insert("ap", replica("ap", Some(3)));
+
+    lower.merge(higher);
+    let servers = lower.open();
+
+    assert_eq!(servers.len(), 3);
+    assert_eq!(servers["eu"], resolved("eu", 10));
+    assert_eq!(servers["us"], resolved("us", 2));
+    assert_eq!(servers["ap"], resolved("ap", 3));
+}
+
+#[test]
+fn maps_apply_entry_by_entry() {
+    let mut target = BTreeMap::new();
+    target.insert("eu", resolved("eu", 1));
+    target.insert("us", resolved("us", 2));
+
+    let mut partial = BTreeMap::new();
+    let mut eu = Replica::new_empty();
+    eu.port = Some(10);
+    partial.insert("eu", eu);
+    partial.insert("ap", replica("ap", Some(3)));
+
+    let changes = partial.apply_to(&mut target).unwrap();
+
+    assert_eq!(target["eu"], resolved("eu", 10));
+    assert_eq!(target["us"], resolved("us", 2));
+    assert_eq!(target["ap"], resolved("ap", 3));
+    assert_eq!(
+        changes.iter().collect::<Vec<_>>(),
+        vec!["[\"ap\"]", "[\"eu\"].port"]
+    );
+}
+
+#[test]
+fn maps_with_an_entry_which_fails_to_open_apply_nothing() {
+    let mut target = BTreeMap::new();
+    target.insert("eu", resolved("eu", 1));
+
+    let mut partial = BTreeMap::new();
+    let mut eu = Replica::new_empty();
+    eu.port = Some(10);
+    partial.insert("eu", eu);
+    partial.insert("us", replica("us", None));
+
+    assert!(partial.apply_to(&mut target).is_err());
+    assert_eq!(target.len(), 1);
+    assert_eq!(target["eu"], resolved("eu", 1));
+}
+
+#[test]
+fn sequences_are_replaced_as_a_whole() {
+    let mut lower = vec![replica("a", Some(1)), replica("b", Some(2))];
+    lower.merge(vec![replica("c", Some(3))]);
+    assert_eq!(lower.open(), vec![resolved("c", 3)]);
+
+    let mut lower = vec![replica("a", Some(1))];
+    lower.merge(vec![]);
+    assert_eq!(lower.open(), vec![resolved("a", 1)]);
+}
+
+#[test]
+fn pointers_open_into_pointers() {
+    let mut boxed = Box::new(replica("a", Some(1)));
+    assert_eq!(boxed.open(), Box::new(resolved("a", 1)));
+
+    let mut shared = Rc::new(Some(5));
+    let other = Rc::clone(&shared);
+    assert_eq!(shared.open(), Rc::new(5));
+    assert_eq!(*other, Some(5));
+
+    let mut shared = Arc::new(vec![Some(1), Some(2)]);
+    assert_eq!(shared.open(), Arc::new(vec![1, 2]));
+}
+
+#[test]
+fn boxes_merge_what_they_hold() {
+    let mut lower = Box::new(replica("a", Some(1)));
+    let mut higher = Replica::new_empty();
+    higher.port = Some(2);
+
+    lower.merge(Box::new(higher));
+
+    assert_eq!(lower.open(), Box::new(resolved("a", 2)));
+}
+
+#[test]
+fn arrays_and_tuples_open_element_by_element() {
+    let mut replicas = [replica("a", Some(1)), replica("b", Some(2))];
+    assert_eq!(replicas.open(), [resolved("a", 1), resolved("b", 2)]);
+
+    let mut pair = (Some(1), replica("a", None));
+    let error = pair.try_open().unwrap_err();
+    assert_eq!(error.path().to_string(), "1.port");
+
+    let mut pair = (Some(1), replica("a", Some(2)));
+    assert_eq!(pair.open(), (1, resolved("a", 2)));
+}
+
+#[test]
+fn arrays_and_tuples_apply_element_by_element() {
+    let mut target = [resolved("a", 1), resolved("b", 2)];
+    let mut partial = [Replica::new_empty(), Replica::new_empty()];
+    partial[1].port = Some(20);
+
+    let changes = partial.apply_to(&mut target).unwrap();
+
+    assert_eq!(target, [resolved("a", 1), resolved("b", 20)]);
+    assert_eq!(changes.iter().collect::<Vec<_>>(), vec!["[1].port"]);
+
+    let mut target = (1, resolved("a", 1));
+    let changes = (None, replica("b", None)).apply_to(&mut target).unwrap();
+
+    assert_eq!(target, (1, resolved("b", 1)));
+    assert_eq!(changes.iter().collect::<Vec<_>>(), vec!["1.host"]);
+}
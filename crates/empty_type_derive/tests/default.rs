@@ -34,6 +34,7 @@ fn empty_type_can_be_instantiated() {
 }
 
 #[test]
+#[allow(clippy::bool_assert_comparison)]
 fn unwrapping_produces_default_value() {
     let empty = TestStruct::new_empty();
     let full = empty.resolve();
@@ -0,0 +1,89 @@
+/*
+ * Copyright [2022] [Kevin Velasco]
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use empty_type::EmptyType;
+use empty_type_derive::EmptyType;
+
+#[derive(EmptyType)]
+struct Server {
+    #[empty(default_value = 8080)]
+    port: u16,
+    #[empty(default = "default_host")]
+    host: String,
+    #[empty(default_value = vec![String::from("/health")])]
+    routes: Vec<String>,
+    #[empty(default)]
+    workers: usize,
+}
+
+fn default_host() -> String {
+    "localhost".to_owned()
+}
+
+#[derive(EmptyType)]
+#[empty(fail_safe)]
+struct Limits {
+    #[empty(default_value = 100)]
+    requests: u32,
+    connections: u32,
+}
+
+#[derive(EmptyType)]
+enum Listener {
+    Tcp {
+        #[empty(default_value = 8080)]
+        port: u16,
+    },
+}
+
+#[test]
+fn unset_fields_resolve_to_their_defaults() {
+    let server = Server::new_empty().resolve();
+
+    assert_eq!(server.port, 8080);
+    assert_eq!(server.host, "localhost");
+    assert_eq!(server.routes, vec!["/health".to_owned()]);
+    assert_eq!(server.workers, 0);
+}
+
+#[test]
+fn set_fields_take_precedence_over_defaults() {
+    let mut empty = Server::new_empty();
+    empty.port = Some(443);
+    empty.host = Some("example.com".to_owned());
+
+    let server = empty.resolve();
+
+    assert_eq!(server.port, 443);
+    assert_eq!(server.host, "example.com");
+}
+
+#[test]
+fn defaults_apply_to_fail_safe_containers() {
+    let limits = Limits::new_empty().resolve();
+
+    assert_eq!(limits.requests, 100);
+    assert_eq!(limits.connections, 0);
+}
+
+#[test]
+fn defaults_apply_to_variant_fields() {
+    let mut empty = Listener::new_empty();
+    *empty = ___EmptyListener::Tcp { port: None };
+
+    let Listener::Tcp { port } = empty.resolve();
+    assert_eq!(port, 8080);
+}
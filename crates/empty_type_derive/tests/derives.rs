@@ -0,0 +1,91 @@
+/*
+ * Copyright [2022] [Kevin Velasco]
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use empty_type::{Empty, EmptyType};
+use empty_type_derive::EmptyType;
+use std::collections::HashSet;
+
+#[allow(dead_code)]
+#[derive(EmptyType)]
+#[empty(derive(Debug, Clone, PartialEq, Eq, Hash))]
+struct Config {
+    port: u16,
+    user: Option<String>,
+    #[empty(nested)]
+    database: Database,
+    #[empty(fail_safe)]
+    retries: u8,
+}
+
+#[allow(dead_code)]
+#[derive(EmptyType)]
+#[empty(derive(Debug, Clone, PartialEq, Eq, Hash))]
+struct Database {
+    host: String,
+}
+
+#[allow(dead_code)]
+#[derive(EmptyType)]
+#[empty(derive(Debug, Clone, PartialEq), attr(allow(dead_code)))]
+enum Mode {
+    Fast,
+    Slow { delay: u32 },
+}
+
+fn partial_config() -> Empty<Config> {
+    let mut empty = Config::new_empty();
+    empty.port = Some(8080);
+    empty.database.host = Some("localhost".to_owned());
+    empty
+}
+
+#[test]
+fn partial_values_can_be_cloned_and_compared() {
+    let empty = partial_config();
+    let mut copy = empty.clone();
+
+    assert_eq!(empty, copy);
+
+    copy.port = Some(443);
+    assert_ne!(empty, copy);
+}
+
+#[test]
+fn partial_values_can_be_hashed() {
+    let mut set = HashSet::new();
+    set.insert(partial_config());
+    set.insert(partial_config());
+
+    assert_eq!(set.len(), 1);
+}
+
+#[test]
+fn partial_values_can_be_debug_printed() {
+    let printed = format!("{:?}", partial_config());
+
+    assert!(printed.contains("port: Some(8080)"));
+    assert!(printed.contains("host: Some(\"localhost\")"));
+}
+
+#[test]
+fn enum_containers_can_be_compared() {
+    let mut empty = Mode::new_empty();
+    assert_eq!(empty, Mode::new_empty());
+
+    *empty = ___EmptyMode::Slow { delay: Some(10) };
+    assert_ne!(empty, Mode::new_empty());
+    assert_eq!(empty.clone(), empty);
+}
@@ -0,0 +1,140 @@
+/*
+ * Copyright [2022] [Kevin Velasco]
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use empty_type::EmptyType;
+use empty_type_derive::EmptyType;
+
+#[derive(EmptyType, Debug, Clone, PartialEq)]
+#[empty(diff)]
+struct Resource {
+    name: String,
+    description: Option<String>,
+    archived: bool,
+    #[empty(nested)]
+    owner: Owner,
+    #[empty(nested)]
+    visibility: Visibility,
+    #[empty(fail_safe)]
+    revision: u32,
+}
+
+#[derive(EmptyType, Debug, Clone, PartialEq)]
+#[empty(diff)]
+struct Owner(u64, String);
+
+#[derive(EmptyType, Debug, Clone, PartialEq)]
+#[empty(diff)]
+enum Visibility {
+    Public,
+    Team { id: u64, editable: bool },
+}
+
+fn resource() -> Resource {
+    Resource {
+        name: "service".to_owned(),
+        description: Some("a service".to_owned()),
+        archived: false,
+        owner: Owner(1, "owner@example.com".to_owned()),
+        visibility: Visibility::Team {
+            id: 7,
+            editable: false,
+        },
+        revision: 1,
+    }
+}
+
+#[test]
+fn equal_values_have_an_empty_diff() {
+    let diff = Resource::diff(&resource(), &resource());
+    let changes = diff.apply_to(&mut resource()).unwrap();
+
+    assert!(changes.is_empty());
+}
+
+#[test]
+fn only_changed_fields_are_set() {
+    let mut new = resource();
+    new.name = "renamed".to_owned();
+    new.description = None;
+    new.revision = 2;
+
+    let diff = Resource::diff(&resource(), &new);
+
+    assert_eq!(diff.name, Some("renamed".to_owned()));
+    assert!(!empty_type::Container::is_unset(&diff.description));
+    assert_eq!(*diff.revision, Some(2));
+    let owner = &*diff.owner;
+    assert!(owner.0.is_none());
+    assert!(owner.1.is_none());
+}
+
+#[test]
+fn nested_values_are_diffed_recursively() {
+    let mut new = resource();
+    new.owner.1 = "new@example.com".to_owned();
+    new.visibility = Visibility::Team {
+        id: 7,
+        editable: true,
+    };
+
+    let diff = Resource::diff(&resource(), &new);
+
+    let owner = &*diff.owner;
+    assert!(owner.0.is_none());
+    assert_eq!(owner.1, Some("new@example.com".to_owned()));
+    assert!(matches!(
+        &*diff.visibility,
+        ___EmptyVisibility::Team {
+            id: None,
+            editable: Some(true)
+        }
+    ));
+}
+
+#[test]
+fn applying_a_diff_gives_the_new_value() {
+    let mut new = resource();
+    new.archived = true;
+    new.owner.0 = 2;
+    new.visibility = Visibility::Public;
+
+    let mut old = resource();
+    let changes = Resource::diff(&old, &new).apply_to(&mut old).unwrap();
+
+    assert_eq!(old, new);
+    assert!(changes.contains("archived"));
+    assert!(changes.contains("owner.0"));
+    assert!(changes.contains("visibility"));
+}
+
+#[test]
+fn bools_changed_to_false_round_trip() {
+    let mut old = resource();
+    old.archived = true;
+    old.visibility = Visibility::Team {
+        id: 7,
+        editable: true,
+    };
+    let new = resource();
+
+    let diff = Resource::diff(&old, &new);
+    assert_eq!(diff.archived, Some(false));
+
+    let changes = diff.apply_to(&mut old).unwrap();
+    assert_eq!(old, new);
+    assert!(changes.contains("archived"));
+    assert!(changes.contains("visibility.Team::editable"));
+}
@@ -0,0 +1,95 @@
+/*
+ * Copyright [2022] [Kevin Velasco]
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use empty_type::{Container, EmptyType};
+use empty_type_derive::EmptyType;
+
+#[derive(EmptyType, Debug, PartialEq)]
+enum Message {
+    Ping,
+    Text(String),
+    Move {
+        x: i32,
+        y: i32,
+        label: Option<String>,
+    },
+}
+
+#[derive(EmptyType, Debug, PartialEq)]
+#[empty(fail_safe)]
+enum Defaulted {
+    Value { count: usize },
+}
+
+#[test]
+fn empty_enum_has_no_variant_selected() {
+    let empty = Message::new_empty();
+    assert!(matches!(&*empty, ___EmptyMessage::___Unselected));
+
+    let error = empty.try_resolve().unwrap_err();
+    assert_eq!(error.to_string(), "No variant of `Message` was selected");
+}
+
+#[test]
+fn unit_variants_resolve() {
+    let mut container = ___EmptyMessage::Ping;
+    assert_eq!(container.open(), Message::Ping);
+}
+
+#[test]
+fn tuple_variants_resolve() {
+    let mut container = ___EmptyMessage::Text(Some(String::from("hello")));
+    assert_eq!(container.open(), Message::Text(String::from("hello")));
+}
+
+#[test]
+fn struct_variants_resolve() {
+    let mut container = ___EmptyMessage::Move {
+        x: Some(1),
+        y: Some(2),
+        label: Default::default(),
+    };
+
+    assert_eq!(
+        container.open(),
+        Message::Move {
+            x: 1,
+            y: 2,
+            label: None
+        }
+    );
+}
+
+#[test]
+fn missing_variant_fields_are_reported() {
+    let mut container = ___EmptyMessage::Move {
+        x: Some(1),
+        y: None,
+        label: Default::default(),
+    };
+
+    let error = container.try_open().unwrap_err();
+    assert!(error.to_string().contains("Move::y"), "{}", error);
+}
+
+#[test]
+fn fail_safe_variants_resolve_to_default() {
+    let mut container = ___EmptyDefaulted::Value {
+        count: Default::default(),
+    };
+
+    assert_eq!(container.open(), Defaulted::Value { count: 0 });
+}
@@ -0,0 +1,173 @@
+/*
+ * Copyright [2022] [Kevin Velasco]
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use empty_type::{Container, Empty, EmptyType};
+use empty_type_derive::EmptyType;
+use serde::Deserialize;
+
+#[derive(EmptyType, Debug)]
+#[empty(env, derive(Debug))]
+struct Config {
+    port: u16,
+    host: String,
+    user: Option<String>,
+    verbose: bool,
+    #[empty(nested)]
+    database: Database,
+    #[empty(env = "serde")]
+    level: Level,
+    #[empty(env = "skip")]
+    tags: Vec<String>,
+}
+
+#[derive(EmptyType, Debug)]
+#[empty(env, derive(Debug))]
+struct Database {
+    host: String,
+    max_connections: u32,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum Level {
+    Debug,
+    Info,
+}
+
+#[derive(EmptyType, Debug)]
+#[empty(env, fail_safe)]
+struct Limits(u32, Option<u32>);
+
+#[test]
+fn fields_are_read_from_their_variables() {
+    let config = Empty::<Config>::from_env_vars(
+        "APP",
+        vec![
+            ("APP_PORT", "8080"),
+            ("APP_HOST", "localhost"),
+            ("APP_USER", "admin"),
+            ("APP_VERBOSE", "true"),
+            ("APP_DATABASE__HOST", "db"),
+            ("APP_DATABASE__MAX_CONNECTIONS", "16"),
+            ("APP_LEVEL", "debug"),
+        ],
+    )
+    .unwrap();
+
+    assert_eq!(config.port, Some(8080));
+    assert_eq!(config.host.as_deref(), Some("localhost"));
+    assert_eq!(config.user.as_deref(), Some("admin"));
+    assert_eq!(config.verbose, Some(true));
+    assert_eq!(config.database.host.as_deref(), Some("db"));
+    assert_eq!(config.database.max_connections, Some(16));
+    assert_eq!(config.level, Some(Level::Debug));
+}
+
+#[test]
+fn missing_variables_leave_their_fields_unset() {
+    let config = Empty::<Config>::from_env_vars("APP", vec![("APP_PORT", "8080")]).unwrap();
+
+    assert_eq!(config.port, Some(8080));
+    assert!(config.host.is_unset());
+    assert!(config.database.is_unset());
+}
+
+#[test]
+fn variables_of_other_prefixes_are_ignored() {
+    let config = Empty::<Config>::from_env_vars(
+        "APP",
+        vec![
+            ("OTHER_PORT", "8080"),
+            ("PORT", "80"),
+            ("APP_DATABASE_HOST", "db"),
+        ],
+    )
+    .unwrap();
+
+    assert!(config.port.is_unset());
+    assert!(config.database.is_unset());
+}
+
+#[test]
+fn skipped_fields_are_never_read() {
+    let config = Empty::<Config>::from_env_vars("APP", vec![("APP_TAGS", "a,b")]).unwrap();
+
+    assert!(config.tags.is_unset());
+}
+
+#[test]
+fn unparseable_variables_are_reported_by_name() {
+    let error = Empty::<Config>::from_env_vars("APP", vec![("APP_PORT", "eighty")]).unwrap_err();
+
+    assert_eq!(error.len(), 1);
+    assert_eq!(
+        error.to_string(),
+        "Failed to parse `APP_PORT`: invalid digit found in string"
+    );
+}
+
+#[test]
+fn every_unparseable_variable_is_reported() {
+    let error = Empty::<Config>::from_env_vars(
+        "APP",
+        vec![
+            ("APP_PORT", "eighty"),
+            ("APP_DATABASE__MAX_CONNECTIONS", "-1"),
+            ("APP_LEVEL", "trace"),
+        ],
+    )
+    .unwrap_err();
+
+    let variables: Vec<_> = error.iter().map(|(name, _)| name).collect();
+    assert_eq!(
+        variables,
+        ["APP_PORT", "APP_DATABASE__MAX_CONNECTIONS", "APP_LEVEL"]
+    );
+    assert!(error
+        .to_string()
+        .starts_with("Failed to parse 3 variables:\n- `APP_PORT`: "));
+}
+
+#[test]
+fn positional_fields_are_read_by_index() {
+    let limits =
+        Empty::<Limits>::from_env_vars("LIMITS", vec![("LIMITS_0", "5"), ("LIMITS_1", "9")])
+            .unwrap()
+            .resolve();
+
+    assert_eq!(limits.0, 5);
+    assert_eq!(limits.1, Some(9));
+}
+
+#[test]
+fn an_empty_prefix_reads_bare_names() {
+    let config = Empty::<Database>::from_env_vars("", vec![("HOST", "db")]).unwrap();
+
+    assert_eq!(config.host.as_deref(), Some("db"));
+}
+
+#[test]
+fn the_environment_layers_over_other_sources() {
+    let mut defaults = Config::new_empty();
+    defaults.port = Some(80);
+    defaults.host = Some("localhost".to_owned());
+
+    let env = Empty::<Config>::from_env_vars("APP", vec![("APP_PORT", "8080")]).unwrap();
+    let config = defaults.overlay(env);
+
+    assert_eq!(config.port, Some(8080));
+    assert_eq!(config.host.as_deref(), Some("localhost"));
+}
@@ -0,0 +1,116 @@
+/*
+ * Copyright [2022] [Kevin Velasco]
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use empty_type::{Container, EmptyType, ResolveError};
+use empty_type_derive::EmptyType;
+
+#[derive(EmptyType, Debug)]
+struct Config {
+    name: String,
+    port: u16,
+    #[empty(default_value = 4)]
+    workers: u8,
+    #[empty(nested)]
+    database: Database,
+}
+
+#[derive(EmptyType, Debug)]
+struct Database {
+    host: String,
+    user: String,
+}
+
+#[derive(EmptyType, Debug)]
+#[empty(fail_fast)]
+struct FailFast {
+    name: String,
+    port: u16,
+}
+
+#[derive(EmptyType, Debug)]
+enum Listener {
+    Tcp { host: String, port: u16 },
+}
+
+#[test]
+fn every_missing_field_is_reported() {
+    let mut container = Config::new_container();
+    container.database.host = Some("localhost".to_owned());
+
+    let error = container.try_open().unwrap_err();
+    let errors = match &error {
+        ResolveError::Fields(errors) => errors,
+        error => panic!("expected every field to be reported, got {:?}", error),
+    };
+    assert_eq!(errors.len(), 3);
+
+    let message = error.to_string();
+    assert!(message.contains("`name`"), "{}", message);
+    assert!(message.contains("`port`"), "{}", message);
+    assert!(message.contains("`database.user`"), "{}", message);
+    assert!(!message.contains("`workers`"), "{}", message);
+}
+
+#[test]
+fn a_single_missing_field_is_reported_on_its_own() {
+    let mut container = Database::new_container();
+    container.host = Some("localhost".to_owned());
+
+    let error = container.try_open().unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Failed to resolve field `user`. Opened to `None` value"
+    );
+}
+
+#[test]
+fn fail_fast_containers_report_the_first_missing_field() {
+    let error = FailFast::new_container().try_open().unwrap_err();
+
+    assert!(matches!(error, ResolveError::Missing { .. }), "{:?}", error);
+    assert!(error.to_string().contains("`name`"), "{}", error);
+    assert!(!error.to_string().contains("`port`"), "{}", error);
+}
+
+#[test]
+fn every_missing_variant_field_is_reported() {
+    let mut container = ___EmptyListener::Tcp {
+        host: None,
+        port: None,
+    };
+
+    let error = container.try_open().unwrap_err();
+    assert!(error.to_string().contains("Tcp::host"), "{}", error);
+    assert!(error.to_string().contains("Tcp::port"), "{}", error);
+}
+
+#[test]
+fn the_count_includes_the_fields_of_nested_containers() {
+    let error = Config::new_container().try_open().unwrap_err();
+    let errors = match &error {
+        ResolveError::Fields(errors) => errors,
+        error => panic!("expected every field to be reported, got {:?}", error),
+    };
+    assert_eq!(errors.len(), 3);
+
+    let message = errors.to_string();
+    assert!(
+        message.starts_with("Failed to resolve 4 fields:"),
+        "{}",
+        message
+    );
+    assert_eq!(message, error.to_string());
+}
@@ -0,0 +1,124 @@
+/*
+ * Copyright [2022] [Kevin Velasco]
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use empty_type::{Container, EmptyType, Field};
+use empty_type_derive::EmptyType;
+
+#[derive(EmptyType, Debug)]
+struct Config {
+    name: String,
+    #[empty(nested)]
+    database: Database,
+}
+
+#[derive(EmptyType, Debug)]
+struct Database {
+    #[empty(nested)]
+    primary: Replica,
+    #[empty(nested)]
+    secondary: Replica,
+}
+
+#[derive(EmptyType, Debug)]
+struct Replica {
+    host: String,
+    port: u16,
+}
+
+#[derive(EmptyType, Debug)]
+struct Cluster {
+    #[empty(nested)]
+    database: Pool,
+}
+
+#[derive(EmptyType, Debug)]
+struct Pool {
+    #[empty(nested)]
+    replicas: Vec<Replica>,
+}
+
+fn partial_config() -> ___EmptyConfig {
+    let mut container = Config::new_container();
+    container.name = Some("service".to_owned());
+    container.database.primary.host = Some("primary".to_owned());
+    container.database.primary.port = Some(5432);
+    container.database.secondary.port = Some(5432);
+    container
+}
+
+#[test]
+fn errors_carry_the_full_path() {
+    let error = partial_config().try_open().unwrap_err();
+
+    assert_eq!(
+        error.path().segments(),
+        &[
+            Field::Named("database"),
+            Field::Named("secondary"),
+            Field::Named("host")
+        ]
+    );
+    assert_eq!(error.path().to_string(), "database.secondary.host");
+    assert_eq!(
+        error.to_string(),
+        "Failed to resolve field `database.secondary.host`. Opened to `None` value"
+    );
+}
+
+#[test]
+fn every_failure_has_its_own_path() {
+    let mut container = partial_config();
+    container.name = None;
+    container.database.primary.port = None;
+
+    let error = container.try_open().unwrap_err();
+    let paths: Vec<_> = error
+        .failures()
+        .into_iter()
+        .map(|(path, _)| path.to_string())
+        .collect();
+
+    assert_eq!(
+        paths,
+        vec!["name", "database.primary.port", "database.secondary.host"]
+    );
+}
+
+#[test]
+fn indexes_are_displayed_in_brackets() {
+    let mut replica = Replica::new_empty();
+    replica.port = Some(5432);
+    let mut replicas = vec![Replica::new_empty(), Replica::new_empty(), replica];
+    for replica in &mut replicas[..2] {
+        replica.host = Some("replica".to_owned());
+        replica.port = Some(5432);
+    }
+
+    let mut container = Cluster::new_container();
+    container.database.replicas = replicas;
+
+    let error = container.try_open().unwrap_err();
+    assert_eq!(
+        error.path().segments(),
+        &[
+            Field::Named("database"),
+            Field::Named("replicas"),
+            Field::Index(2),
+            Field::Named("host")
+        ]
+    );
+    assert_eq!(error.path().to_string(), "database.replicas[2].host");
+}
@@ -0,0 +1,98 @@
+/*
+ * Copyright [2022] [Kevin Velasco]
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use empty_type::{Container, Empty, EmptyType};
+use empty_type_derive::EmptyType;
+
+#[derive(EmptyType, Debug, Clone, PartialEq)]
+struct Config {
+    port: u16,
+    user: Option<String>,
+    verbose: bool,
+    #[empty(nested)]
+    database: Database,
+    #[empty(fail_safe)]
+    retries: u8,
+}
+
+#[derive(EmptyType, Debug, Clone, PartialEq)]
+struct Database {
+    host: String,
+}
+
+#[derive(EmptyType, Debug, Clone, PartialEq)]
+#[empty(fail_safe)]
+struct Pair<T: Default>(T, Option<T>);
+
+#[derive(EmptyType, Debug, Clone, PartialEq)]
+enum Shape {
+    Circle { radius: u32 },
+    Point,
+}
+
+fn config() -> Config {
+    Config {
+        port: 8080,
+        user: None,
+        verbose: true,
+        database: Database {
+            host: "localhost".to_owned(),
+        },
+        retries: 3,
+    }
+}
+
+#[test]
+fn full_values_round_trip_through_their_container() {
+    let empty = config().into_empty();
+
+    assert_eq!(empty.resolve(), config());
+}
+
+#[test]
+fn every_field_of_the_container_is_set() {
+    let empty = config().into_empty();
+
+    assert_eq!(empty.port, Some(8080));
+    assert!(!empty.user.is_unset());
+    assert_eq!(empty.database.host, Some("localhost".to_owned()));
+}
+
+#[test]
+fn containers_can_be_edited_from_existing_values() {
+    let mut empty: Empty<Config> = config().into();
+    empty.database.host = Some("example.com".to_owned());
+
+    let edited = empty.resolve();
+    assert_eq!(edited.database.host, "example.com");
+    assert_eq!(edited.port, 8080);
+}
+
+#[test]
+fn generic_tuple_structs_round_trip() {
+    let pair = Pair(1, Some(2));
+    let mut container = ___EmptyPair::from(pair.clone());
+
+    assert_eq!(container.open(), pair);
+}
+
+#[test]
+fn enums_round_trip() {
+    let circle = Shape::Circle { radius: 2 };
+
+    assert_eq!(circle.clone().into_empty().resolve(), circle);
+    assert_eq!(Shape::Point.into_empty().resolve(), Shape::Point);
+}
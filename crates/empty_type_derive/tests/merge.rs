@@ -0,0 +1,143 @@
+/*
+ * Copyright [2022] [Kevin Velasco]
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use empty_type::{Container, Empty, EmptyType};
+use empty_type_derive::EmptyType;
+
+#[derive(EmptyType)]
+struct Config {
+    port: u16,
+    host: String,
+    user: Option<String>,
+    verbose: bool,
+    #[empty(nested)]
+    database: Database,
+    #[empty(fail_safe)]
+    retries: u8,
+}
+
+#[derive(EmptyType)]
+struct Database {
+    host: String,
+    replicas: u8,
+}
+
+#[derive(EmptyType, Debug, PartialEq)]
+enum Transport {
+    Tcp { port: u16, host: String },
+    Unix(String),
+}
+
+fn defaults() -> Empty<Config> {
+    let mut empty = Config::new_empty();
+    empty.port = Some(8080);
+    empty.host = Some("localhost".to_owned());
+    empty.user = Some("admin".to_owned()).into();
+    empty.database.host = Some("localhost".to_owned());
+    empty.database.replicas = Some(1);
+    empty
+}
+
+#[test]
+fn set_fields_in_the_higher_layer_win() {
+    let mut file = Config::new_empty();
+    file.port = Some(443);
+    *file.retries = Some(5);
+
+    let config = defaults().overlay(file).resolve();
+
+    assert_eq!(config.port, 443);
+    assert_eq!(config.host, "localhost");
+    assert_eq!(config.retries, 5);
+}
+
+#[test]
+fn nested_containers_are_merged_field_by_field() {
+    let mut env = Config::new_empty();
+    env.database.replicas = Some(3);
+
+    let config = defaults().overlay(env).resolve();
+
+    assert_eq!(config.database.host, "localhost");
+    assert_eq!(config.database.replicas, 3);
+}
+
+#[test]
+fn optional_fields_explicitly_set_to_none_win() {
+    let config = defaults().overlay(Config::new_empty()).resolve();
+    assert_eq!(config.user, Some("admin".to_owned()));
+
+    let mut cli = Config::new_empty();
+    cli.user = None.into();
+
+    let config = defaults().overlay(cli).resolve();
+    assert_eq!(config.user, None);
+}
+
+#[test]
+fn bools_set_to_false_win_over_lower_layers() {
+    let mut verbose = Config::new_empty();
+    verbose.verbose = Some(true);
+
+    let mut merged = defaults().overlay(verbose);
+    merged.merge(Config::new_empty());
+    assert_eq!(merged.verbose, Some(true));
+
+    let mut quiet = Config::new_empty();
+    quiet.verbose = Some(false);
+    merged.merge(quiet);
+    assert!(!merged.resolve().verbose);
+}
+
+#[test]
+fn layers_can_be_merged_in_place() {
+    let mut layers = vec![defaults(), Config::new_empty(), Config::new_empty()];
+    layers[1].port = Some(1);
+    layers[2].port = Some(2);
+
+    let mut merged = Config::new_empty();
+    for layer in layers {
+        merged.merge(layer);
+    }
+
+    assert_eq!(merged.resolve().port, 2);
+}
+
+#[test]
+fn enum_variants_merge_or_replace() {
+    let mut tcp = Transport::new_empty();
+    *tcp = ___EmptyTransport::Tcp {
+        port: Some(80),
+        host: Some("localhost".to_owned()),
+    };
+
+    let mut port = Transport::new_empty();
+    *port = ___EmptyTransport::Tcp {
+        port: Some(443),
+        host: None,
+    };
+    let merged = tcp.overlay(port).overlay(Transport::new_empty());
+    let tcp = Transport::Tcp {
+        port: 443,
+        host: "localhost".to_owned(),
+    };
+    assert_eq!(merged.resolve(), tcp);
+
+    let mut unix = Transport::new_empty();
+    *unix = ___EmptyTransport::Unix(Some("/tmp/socket".to_owned()));
+    let merged = tcp.into_empty().overlay(unix);
+    assert_eq!(merged.resolve(), Transport::Unix("/tmp/socket".to_owned()));
+}
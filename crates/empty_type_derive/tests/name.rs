@@ -0,0 +1,55 @@
+/*
+ * Copyright [2022] [Kevin Velasco]
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use empty_type::{Container, EmptyType};
+use empty_type_derive::EmptyType;
+
+mod config {
+    use empty_type_derive::EmptyType;
+
+    /// Service configuration
+    #[derive(EmptyType, Debug, PartialEq)]
+    #[empty(name = "PartialConfig", vis = "pub(crate)")]
+    pub(crate) struct Config {
+        /// Port the service listens on
+        pub port: u16,
+    }
+}
+
+#[derive(EmptyType, Debug, PartialEq)]
+#[empty(name = "PartialMessage")]
+enum Message {
+    Ping,
+}
+
+#[test]
+fn container_can_be_named() {
+    let mut partial = config::PartialConfig { port: Some(8080) };
+
+    assert_eq!(partial.open(), config::Config { port: 8080 });
+}
+
+#[test]
+fn named_container_is_the_empty_container() {
+    let container: <config::Config as EmptyType>::Container = config::PartialConfig::default();
+    assert!(container.port.is_none());
+}
+
+#[test]
+fn enum_container_can_be_named() {
+    let mut partial = PartialMessage::Ping;
+    assert_eq!(partial.open(), Message::Ping);
+}
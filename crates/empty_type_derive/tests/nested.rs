@@ -0,0 +1,123 @@
+/*
+ * Copyright [2022] [Kevin Velasco]
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use empty_type::{Container, EmptyType};
+use empty_type_derive::EmptyType;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+#[derive(EmptyType, Debug, PartialEq)]
+struct Config {
+    name: String,
+    #[empty(nested)]
+    database: Database,
+}
+
+#[derive(EmptyType, Debug, PartialEq, Clone)]
+#[empty(derive(Clone))]
+struct Database {
+    host: String,
+    port: u16,
+}
+
+// Collections and pointers hold the containers of their elements
+#[derive(EmptyType, Debug, PartialEq)]
+struct Cluster {
+    #[empty(nested)]
+    replicas: Vec<Database>,
+    #[empty(nested)]
+    regions: HashMap<String, Database>,
+    #[empty(nested)]
+    primary: Box<Database>,
+    #[empty(nested)]
+    shared: Rc<Database>,
+    #[empty(nested)]
+    pair: [Database; 2],
+    #[empty(nested)]
+    named: (Database, Database),
+}
+
+fn database(host: &str) -> Database {
+    Database {
+        host: host.to_owned(),
+        port: 5432,
+    }
+}
+
+fn cluster() -> Cluster {
+    Cluster {
+        replicas: vec![database("a"), database("b")],
+        regions: vec![("eu".to_owned(), database("eu"))]
+            .into_iter()
+            .collect(),
+        primary: Box::new(database("primary")),
+        shared: Rc::new(database("shared")),
+        pair: [database("left"), database("right")],
+        named: (database("first"), database("second")),
+    }
+}
+
+#[test]
+fn nested_fields_hold_containers() {
+    let mut empty = Config::new_empty();
+    empty.name = Some(String::from("service"));
+    empty.database.host = Some(String::from("localhost"));
+    empty.database.port = Some(5432);
+
+    assert_eq!(
+        empty.resolve(),
+        Config {
+            name: String::from("service"),
+            database: Database {
+                host: String::from("localhost"),
+                port: 5432
+            }
+        }
+    );
+}
+
+#[test]
+fn missing_nested_fields_are_reported() {
+    let mut container = Config::new_container();
+    container.name = Some(String::from("service"));
+    container.database.host = Some(String::from("localhost"));
+
+    let error = container.try_open().unwrap_err().to_string();
+    assert!(error.contains("`database.port`"), "{}", error);
+}
+
+#[test]
+fn nested_collections_hold_containers_of_their_elements() {
+    let mut empty = cluster().into_empty();
+    let _: &Vec<empty_type::Empty<Database>> = &empty.replicas;
+    let _: &HashMap<String, empty_type::Empty<Database>> = &empty.regions;
+
+    empty.replicas[1].port = None;
+    empty.regions.get_mut("eu").unwrap().host = None;
+
+    let error = empty.try_open().unwrap_err();
+    let paths: Vec<_> = error
+        .failures()
+        .into_iter()
+        .map(|(path, _)| path.to_string())
+        .collect();
+    assert_eq!(paths, vec!["replicas[1].port", r#"regions["eu"].host"#]);
+}
+
+#[test]
+fn nested_collections_convert_and_resolve() {
+    assert_eq!(cluster().into_empty().resolve(), cluster());
+}
@@ -0,0 +1,184 @@
+/*
+ * Copyright [2022] [Kevin Velasco]
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use empty_type::{Container, Empty, EmptyType, OverrideError};
+use empty_type_derive::EmptyType;
+use serde::Deserialize;
+
+#[derive(EmptyType, Deserialize)]
+#[empty(overrides)]
+#[serde(rename_all = "kebab-case")]
+struct Config {
+    #[empty(nested)]
+    server: Server,
+    log_level: Option<String>,
+    #[empty(fail_safe)]
+    retries: u8,
+    #[serde(rename = "mode")]
+    #[empty(overrides = "serde")]
+    run_mode: Mode,
+    #[empty(overrides = "skip")]
+    tags: Vec<String>,
+}
+
+#[derive(EmptyType, Deserialize)]
+#[empty(overrides)]
+struct Server {
+    port: u16,
+    verbose: bool,
+    #[empty(nested)]
+    tls: Tls,
+}
+
+#[derive(EmptyType, Deserialize)]
+#[empty(overrides, fail_safe)]
+struct Tls {
+    cert: String,
+    #[serde(rename(deserialize = "private_key"))]
+    key: String,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum Mode {
+    Fast,
+    Safe,
+}
+
+#[test]
+fn fields_are_set_by_their_path() {
+    let mut config = Config::new_empty();
+    config.set_path("server.port", "8080").unwrap();
+    config.set_path("server.verbose", "true").unwrap();
+    config.set_path("server.tls.cert", "path/to/cert").unwrap();
+
+    assert_eq!(config.server.port, Some(8080));
+    assert_eq!(config.server.verbose, Some(true));
+    assert_eq!(config.server.tls.cert.as_deref(), Some("path/to/cert"));
+    assert!(config.server.tls.key.is_unset());
+}
+
+#[test]
+fn optional_and_fallible_fields_are_set() {
+    let mut config = Config::new_empty();
+    config.set_path("log-level", "debug").unwrap();
+    config.set_path("retries", "3").unwrap();
+
+    assert_eq!(config.log_level.as_deref(), Some("debug"));
+    assert_eq!(*config.retries, Some(3));
+}
+
+#[test]
+fn fields_are_named_as_they_are_deserialized() {
+    let mut config = Config::new_empty();
+    config.set_path("mode", "safe").unwrap();
+    config
+        .set_path("server.tls.private_key", "key.pem")
+        .unwrap();
+
+    assert_eq!(config.run_mode, Some(Mode::Safe));
+    assert_eq!(config.server.tls.key.as_deref(), Some("key.pem"));
+
+    let error = config.set_path("log_level", "debug").unwrap_err();
+    assert_eq!(error, OverrideError::unknown_path("log_level"));
+}
+
+#[test]
+fn unknown_paths_are_reported_in_full() {
+    let mut config = Config::new_empty();
+
+    let error = config
+        .set_path("server.tls.chain", "chain.pem")
+        .unwrap_err();
+    assert_eq!(error.to_string(), "No field at `server.tls.chain`");
+
+    let error = config.set_path("server.port.number", "80").unwrap_err();
+    assert_eq!(error.path(), Some("server.port.number"));
+
+    let error = config.set_path("tags", "a").unwrap_err();
+    assert_eq!(error.path(), Some("tags"));
+}
+
+#[test]
+fn values_which_fail_to_parse_are_reported() {
+    let mut config = Config::new_empty();
+
+    let error = config.set_path("server.port", "http").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Failed to parse `server.port`: invalid digit found in string"
+    );
+    assert!(config.server.port.is_unset());
+
+    let error = config.set_path("mode", "slow").unwrap_err();
+    assert_eq!(error.path(), Some("mode"));
+}
+
+#[test]
+fn nested_containers_need_the_path_to_a_field() {
+    let mut config = Config::new_empty();
+
+    let error = config.set_path("server.tls", "cert.pem").unwrap_err();
+    assert!(matches!(error, OverrideError::Invalid { path, .. } if path == "server.tls"));
+}
+
+#[test]
+fn overrides_are_applied_in_a_batch() {
+    let mut config = Config::new_empty();
+    config
+        .apply_overrides(["server.port=8080", "server.tls.cert=a=b"])
+        .unwrap();
+
+    assert_eq!(config.server.port, Some(8080));
+    assert_eq!(config.server.tls.cert.as_deref(), Some("a=b"));
+}
+
+#[test]
+fn every_failed_override_is_reported() {
+    let mut config = Config::new_empty();
+    let errors = config
+        .apply_overrides(vec![
+            "server.port=http".to_owned(),
+            "server.host=localhost".to_owned(),
+            "verbose".to_owned(),
+            "retries=2".to_owned(),
+        ])
+        .unwrap_err();
+
+    assert_eq!(
+        errors,
+        [
+            OverrideError::invalid("server.port", "invalid digit found in string"),
+            OverrideError::unknown_path("server.host"),
+            OverrideError::Malformed("verbose".to_owned()),
+        ]
+    );
+    assert_eq!(*config.retries, Some(2));
+}
+
+#[test]
+fn overrides_layer_over_other_sources() {
+    let mut file = Config::new_empty();
+    file.server.port = Some(80);
+    file.server.tls.cert = Some("file.pem".to_owned()).into();
+
+    let mut cli = Empty::<Config>::default();
+    cli.set_path("server.port", "8080").unwrap();
+    let config = file.overlay(cli);
+
+    assert_eq!(config.server.port, Some(8080));
+    assert_eq!(config.server.tls.cert.as_deref(), Some("file.pem"));
+}
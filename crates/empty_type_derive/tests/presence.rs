@@ -0,0 +1,164 @@
+/*
+ * Copyright [2022] [Kevin Velasco]
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use empty_type::{Container, EmptyType};
+use empty_type_derive::EmptyType;
+
+#[derive(EmptyType, Debug)]
+struct Config {
+    port: u16,
+    name: Option<String>,
+    verbose: bool,
+    #[empty(default_value = 3)]
+    retries: u8,
+    #[empty(nested)]
+    database: Database,
+}
+
+#[derive(EmptyType, Debug)]
+struct Database {
+    host: String,
+    #[empty(default)]
+    pool: u32,
+}
+
+#[derive(EmptyType, Debug)]
+#[empty(fail_safe)]
+struct Lenient {
+    port: u16,
+}
+
+#[derive(EmptyType, Debug)]
+struct Pair(u16, String);
+
+#[derive(EmptyType, Debug)]
+enum Transport {
+    Tcp { host: String, port: u16 },
+    Unix(String),
+}
+
+#[test]
+fn lists_missing_fields_with_nested_paths() {
+    let config = Config::new_empty();
+
+    assert_eq!(config.missing_fields(), ["port", "database.host"]);
+    assert!(!config.is_complete());
+}
+
+#[test]
+fn set_fields_are_no_longer_missing() {
+    let mut config = Config::new_empty();
+    config.port = Some(8080);
+    config.database.host = Some("localhost".to_owned());
+
+    assert!(config.missing_fields().is_empty());
+    assert_eq!(config.set_fields(), ["port", "database.host"]);
+    assert!(config.is_complete());
+    assert!(config.try_resolve().is_ok());
+}
+
+#[test]
+fn fail_safe_fields_are_never_missing() {
+    let lenient = Lenient::new_empty();
+
+    assert!(lenient.missing_fields().is_empty());
+    assert!(lenient.is_complete());
+}
+
+#[test]
+fn filled_ratio_counts_set_and_missing_fields() {
+    let mut config = Config::new_empty();
+    assert_eq!(config.filled_ratio(), 0.0);
+
+    config.port = Some(8080);
+    config.verbose = Some(true);
+    // port and verbose are set, database.host is missing
+    assert!((config.filled_ratio() - 2.0 / 3.0).abs() < f64::EPSILON);
+
+    config.database.host = Some("localhost".to_owned());
+    assert_eq!(config.filled_ratio(), 1.0);
+}
+
+#[test]
+fn complete_containers_are_completely_filled() {
+    let lenient = Lenient::new_empty();
+
+    assert_eq!(lenient.filled_ratio(), 1.0);
+}
+
+#[derive(EmptyType, Debug)]
+struct Service {
+    #[empty(nested)]
+    transport: Transport,
+}
+
+#[test]
+fn unselected_enums_are_missing_under_their_name() {
+    let transport = Transport::new_empty();
+    assert_eq!(transport.missing_fields(), ["Transport"]);
+    assert!(transport.set_fields().is_empty());
+}
+
+#[test]
+fn unselected_enums_held_by_a_field_are_missing_under_the_field() {
+    let service = Service::new_empty();
+    assert_eq!(service.missing_fields(), ["transport"]);
+}
+
+#[test]
+fn fields_of_variants_are_named_after_their_variant() {
+    let mut transport = Transport::new_empty();
+    *transport = ___EmptyTransport::Tcp {
+        host: Some("localhost".to_owned()),
+        port: None,
+    };
+    assert_eq!(transport.missing_fields(), ["Tcp::port"]);
+    assert_eq!(transport.set_fields(), ["Tcp::host"]);
+
+    // like they're named by `FIELD_NAMES`
+    for path in transport
+        .missing_fields()
+        .iter()
+        .chain(&transport.set_fields())
+    {
+        assert!(Transport::FIELD_NAMES.contains(&path.as_str()));
+    }
+}
+
+#[test]
+fn collections_list_missing_elements_by_index() {
+    let mut host = Database::new_empty();
+    host.host = Some("localhost".to_owned());
+    let databases = vec![host, Database::new_empty()];
+
+    assert_eq!(
+        databases.missing_fields().iter().collect::<Vec<_>>(),
+        ["[1].host"]
+    );
+}
+
+#[test]
+fn field_names_name_every_field() {
+    assert_eq!(
+        Config::FIELD_NAMES,
+        ["port", "name", "verbose", "retries", "database"]
+    );
+    assert_eq!(Pair::FIELD_NAMES, ["0", "1"]);
+    assert_eq!(
+        Transport::FIELD_NAMES,
+        ["Tcp::host", "Tcp::port", "Unix::0"]
+    );
+}
@@ -0,0 +1,214 @@
+/*
+ * Copyright [2022] [Kevin Velasco]
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use empty_type::{Container, EmptyType, Provenance, Traced};
+use empty_type_derive::EmptyType;
+use std::collections::BTreeMap;
+
+#[derive(EmptyType, Debug)]
+struct Config {
+    port: u16,
+    verbose: bool,
+    #[empty(nested)]
+    database: Database,
+    #[empty(fail_safe)]
+    retries: u8,
+}
+
+#[derive(EmptyType, Debug)]
+struct Database {
+    host: String,
+    pool: Option<u32>,
+}
+
+#[derive(EmptyType, Debug, PartialEq)]
+enum Transport {
+    Tcp { host: String, port: u16 },
+    Unix(String),
+}
+
+#[test]
+fn set_fields_are_listed_by_path() {
+    let mut config = Config::new_empty();
+    config.port = Some(80);
+    config.database.host = Some("localhost".to_owned());
+    *config.retries = Some(3);
+
+    assert_eq!(config.set_fields(), ["port", "database.host", "retries"]);
+}
+
+#[test]
+fn entries_of_maps_are_listed_by_key() {
+    let mut eu = Database::new_empty();
+    eu.host = Some("eu.db".to_owned());
+
+    let mut databases = BTreeMap::new();
+    databases.insert("eu", eu);
+    databases.insert("us", Database::new_empty());
+
+    let set: Vec<_> = databases.set_fields().iter().map(str::to_owned).collect();
+    assert_eq!(set, [r#"["eu"].host"#]);
+}
+
+#[test]
+fn bools_set_to_false_are_set() {
+    let mut config = Config::new_empty();
+    assert!(config.set_fields().is_empty());
+
+    config.verbose = Some(false);
+    assert_eq!(config.set_fields(), ["verbose"]);
+}
+
+#[test]
+fn fields_of_the_selected_variant_are_set() {
+    let mut transport = Transport::new_empty();
+    assert!(transport.set_fields().is_empty());
+
+    *transport = ___EmptyTransport::Tcp {
+        host: None,
+        port: Some(80),
+    };
+    assert_eq!(transport.set_fields(), ["Tcp::port"]);
+}
+
+#[derive(EmptyType, Debug)]
+struct Service {
+    #[empty(nested)]
+    transport: Transport,
+}
+
+// A layer with a TCP transport, setting its port and maybe its host
+fn tcp(host: Option<&str>, port: u16) -> empty_type::Empty<Service> {
+    let mut service = Service::new_empty();
+    *service.transport = ___EmptyTransport::Tcp {
+        host: host.map(str::to_owned),
+        port: Some(port),
+    };
+    service
+}
+
+#[test]
+fn switching_variants_forgets_the_sources_of_the_old_variant() {
+    let mut env = Service::new_empty();
+    *env.transport = ___EmptyTransport::Unix(Some("/run/app.sock".to_owned()));
+
+    let traced = Traced::new()
+        .overlay("file", tcp(Some("localhost"), 80))
+        .overlay("env", env);
+
+    assert_eq!(
+        traced.provenance().iter().collect::<Vec<_>>(),
+        [("transport.Unix::0", "env")]
+    );
+}
+
+#[test]
+fn the_same_variant_keeps_the_sources_of_fields_it_leaves_unset() {
+    let traced = Traced::new()
+        .overlay("file", tcp(Some("localhost"), 80))
+        .overlay("env", tcp(None, 8080));
+
+    assert_eq!(
+        traced.provenance().source("transport.Tcp::host"),
+        Some("file")
+    );
+    assert_eq!(
+        traced.provenance().source("transport.Tcp::port"),
+        Some("env")
+    );
+}
+
+#[test]
+fn the_last_layer_to_set_a_field_is_its_source() {
+    let mut defaults = Config::new_empty();
+    defaults.port = Some(80);
+    defaults.database.host = Some("localhost".to_owned());
+    defaults.database.pool = Some(4).into();
+
+    let mut file = Config::new_empty();
+    file.database.host = Some("db".to_owned());
+
+    let mut cli = Config::new_empty();
+    cli.port = Some(8080);
+
+    let traced = Traced::new()
+        .overlay("default", defaults)
+        .overlay("config.json", file)
+        .overlay("override", cli);
+    let (config, provenance) = traced.try_resolve().unwrap();
+
+    assert_eq!(config.port, 8080);
+    assert_eq!(provenance.source("port"), Some("override"));
+    assert_eq!(provenance.source("database.host"), Some("config.json"));
+    assert_eq!(provenance.source("database.pool"), Some("default"));
+    assert_eq!(provenance.source("retries"), None);
+}
+
+#[test]
+fn sources_can_be_named_per_field() {
+    let mut env = Database::new_empty();
+    env.host = Some("db".to_owned());
+
+    let traced = Traced::new().overlay_with(env, |path| format!("APP_{}", path.to_uppercase()));
+
+    assert_eq!(traced.provenance().source("host"), Some("APP_HOST"));
+}
+
+#[test]
+fn provenance_is_carried_through_merges() {
+    let mut lower = Config::new_empty();
+    lower.port = Some(80);
+    let mut lower = Traced::new().overlay("file", lower);
+
+    let mut higher = Config::new_empty();
+    higher.database.host = Some("db".to_owned());
+    lower.merge(Traced::new().overlay("env", higher));
+
+    assert_eq!(lower.empty().port, Some(80));
+    assert_eq!(lower.provenance().source("port"), Some("file"));
+    assert_eq!(lower.provenance().source("database.host"), Some("env"));
+}
+
+#[test]
+fn a_value_set_as_a_whole_replaces_the_sources_within_it() {
+    let mut layer = Database::new_empty();
+    layer.host = Some("db".to_owned());
+
+    let mut provenance = Provenance::default();
+    provenance.record("file", &layer);
+    provenance.record("override", &Some(1));
+
+    assert_eq!(provenance.iter().collect::<Vec<_>>(), [("", "override")]);
+}
+
+#[test]
+fn provenance_prints_a_report() {
+    let mut defaults = Database::new_empty();
+    defaults.host = Some("localhost".to_owned());
+    defaults.pool = Some(4).into();
+
+    let mut env = Database::new_empty();
+    env.host = Some("db".to_owned());
+
+    let traced = Traced::new()
+        .overlay("default", defaults)
+        .overlay("APP_HOST", env);
+
+    assert_eq!(
+        traced.provenance().to_string(),
+        "host: APP_HOST\npool: default"
+    );
+}
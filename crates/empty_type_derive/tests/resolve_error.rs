@@ -0,0 +1,120 @@
+/*
+ * Copyright [2022] [Kevin Velasco]
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use empty_type::{Container, EmptyType, Field, ResolveError};
+use empty_type_derive::EmptyType;
+use std::error::Error;
+
+#[derive(EmptyType, Debug)]
+struct Config {
+    name: String,
+    #[empty(nested)]
+    database: Database,
+}
+
+#[derive(EmptyType, Debug)]
+struct Database {
+    host: String,
+}
+
+#[derive(EmptyType, Debug)]
+enum Mode {
+    Fast,
+}
+
+// A container validating what it holds
+#[derive(Default)]
+struct Port(Option<u16>);
+
+impl Container for Port {
+    type Value = u16;
+
+    fn try_open(&mut self) -> Result<u16, ResolveError> {
+        match self.0.try_open()? {
+            0 => Err("port can't be 0".into()),
+            port => Ok(port),
+        }
+    }
+}
+
+fn assert_send_sync<T: Send + Sync + 'static>() {}
+
+#[test]
+fn resolve_errors_can_cross_threads() {
+    assert_send_sync::<ResolveError>();
+}
+
+#[test]
+fn resolve_errors_convert_with_the_question_mark() {
+    fn resolve() -> Result<Config, Box<dyn Error + Send + Sync>> {
+        Ok(Config::new_empty().try_resolve()?)
+    }
+
+    assert!(resolve().is_err());
+}
+
+#[test]
+fn missing_fields_are_named() {
+    let mut container = Database::new_container();
+
+    match container.try_open().unwrap_err() {
+        ResolveError::Missing { field } => assert_eq!(field, Some(Field::Named("host"))),
+        error => panic!("unexpected error {:?}", error),
+    }
+}
+
+#[test]
+fn nested_failures_keep_their_source() {
+    let mut container = Config::new_container();
+    container.name = Some("service".to_owned());
+
+    let error = container.try_open().unwrap_err();
+    match &error {
+        ResolveError::Nested { field, source } => {
+            assert_eq!(*field, Field::Named("database"));
+            assert!(matches!(**source, ResolveError::Missing { .. }));
+        }
+        error => panic!("unexpected error {:?}", error),
+    }
+
+    let source = error.source().unwrap();
+    assert_eq!(
+        source.to_string(),
+        "Failed to resolve field `host`. Opened to `None` value"
+    );
+}
+
+#[test]
+fn unselected_enums_are_named() {
+    let error = Mode::new_container().try_open().unwrap_err();
+
+    assert!(matches!(error, ResolveError::Unselected { name: "Mode" }));
+}
+
+#[test]
+fn custom_errors_are_kept() {
+    let error = Port(Some(0)).try_open_with_meta("port").unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        "Failed to resolve field `port`: port can't be 0"
+    );
+    assert!(error.source().is_some());
+    assert!(matches!(
+        Port(None).try_open(),
+        Err(ResolveError::Missing { field: None })
+    ));
+}
@@ -29,7 +29,7 @@ struct TestStruct {
 #[test]
 fn empty_type_can_be_instantiated() {
     let empty = TestStruct::new_empty();
-    assert_eq!(empty.value, false);
+    assert_eq!(empty.value, None);
 }
 
 #[test]
@@ -42,7 +42,7 @@ fn empty_type_can_be_deserialized() {
     "#;
 
     let value: <TestStruct as EmptyType>::Container = serde_json::from_str(json).unwrap();
-    assert!(value.value);
+    assert_eq!(value.value, Some(true));
     assert!(value.valuer.is_some());
 
     assert!(value.missing.is_none());
@@ -0,0 +1,135 @@
+/*
+ * Copyright [2022] [Kevin Velasco]
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use empty_type::{deserialize_empty, Container, EmptyType};
+use empty_type_derive::EmptyType;
+use serde::{Deserialize, Serialize};
+
+// Neither type derives serde, the attributes only apply to the containers
+#[derive(EmptyType)]
+#[empty(
+    deserialize,
+    attr(serde(rename_all = "camelCase", deny_unknown_fields))
+)]
+struct Config {
+    listen_port: u16,
+    #[empty(attr(serde(rename = "db")))]
+    database: String,
+}
+
+#[derive(EmptyType)]
+#[empty(deserialize)]
+enum Mode {
+    #[empty(attr(serde(rename = "fast")))]
+    Fast,
+}
+
+// The attributes the derive would add are already on the fields
+#[derive(EmptyType, Deserialize, Serialize)]
+#[empty(deserialize, serialize)]
+struct Server {
+    #[serde(default)]
+    port: u16,
+    #[serde(default, skip_serializing_if = "is_default")]
+    host: String,
+    #[empty(attr(serde(default)))]
+    name: String,
+}
+
+// Paths name fields the way serde does
+#[derive(EmptyType, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Listener {
+    listen_port: u16,
+    #[serde(rename = "addr")]
+    address: String,
+    r#type: String,
+}
+
+#[derive(EmptyType, Deserialize, Debug)]
+#[serde(rename_all = "snake_case")]
+enum Socket {
+    #[serde(rename_all = "camelCase")]
+    UnixSocket { socket_path: String },
+}
+
+// applies to the field of the type and of its container
+fn is_default<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
+}
+
+#[test]
+fn container_attributes_are_applied() {
+    let json = r#"{ "listenPort": 8080, "db": "postgres" }"#;
+    let mut de = serde_json::Deserializer::from_str(json);
+    let config = deserialize_empty::<Config, _>(&mut de).unwrap().resolve();
+
+    assert_eq!(config.listen_port, 8080);
+    assert_eq!(config.database, "postgres");
+}
+
+#[test]
+fn container_attributes_reject_unknown_fields() {
+    let json = r#"{ "listen_port": 8080 }"#;
+    let mut de = serde_json::Deserializer::from_str(json);
+
+    assert!(deserialize_empty::<Config, _>(&mut de).is_err());
+}
+
+#[test]
+fn variant_attributes_are_applied() {
+    let mut de = serde_json::Deserializer::from_str(r#""fast""#);
+    let mode = deserialize_empty::<Mode, _>(&mut de).unwrap().resolve();
+
+    assert!(matches!(mode, Mode::Fast));
+}
+
+#[test]
+fn own_default_and_skip_attributes_are_kept() {
+    let mut de = serde_json::Deserializer::from_str(r#"{ "host": "localhost" }"#);
+    let server = deserialize_empty::<Server, _>(&mut de).unwrap();
+
+    assert!(server.port.is_none());
+    assert!(server.name.is_none());
+    assert_eq!(server.host.as_deref(), Some("localhost"));
+
+    let mut empty = Server::new_empty();
+    empty.port = Some(80);
+    let json = serde_json::to_value(&empty).unwrap();
+    assert_eq!(json, serde_json::json!({ "port": 80 }));
+}
+
+#[test]
+fn paths_use_serde_names() {
+    assert_eq!(Listener::FIELD_NAMES, &["listenPort", "addr", "type"]);
+    assert_eq!(Socket::FIELD_NAMES, &["unix_socket::socketPath"]);
+
+    let mut listener = Listener::new_container();
+    listener.address = Some("localhost".to_owned());
+    let missing: Vec<_> = listener
+        .missing_fields()
+        .iter()
+        .map(|path| path.to_string())
+        .collect();
+    assert_eq!(missing, vec!["listenPort", "type"]);
+
+    let error = listener.try_open().unwrap_err().to_string();
+    assert!(error.contains("`listenPort`"), "{}", error);
+
+    let mut socket = ___EmptySocket::UnixSocket { socket_path: None };
+    let error = socket.try_open().unwrap_err().to_string();
+    assert!(error.contains("`unix_socket::socketPath`"), "{}", error);
+}
@@ -0,0 +1,154 @@
+/*
+ * Copyright [2022] [Kevin Velasco]
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use empty_type::{deserialize_empty, Container, Empty, EmptyType, Fallible, Field};
+use empty_type_derive::EmptyType;
+use serde::Deserialize;
+
+#[derive(EmptyType, Deserialize, Debug)]
+#[empty(deserialize)]
+struct Config {
+    name: String,
+    #[empty(fail_safe)]
+    retries: u8,
+    #[empty(nested)]
+    database: Database,
+    #[empty(fail_safe = "elements")]
+    ports: Vec<u16>,
+}
+
+#[derive(EmptyType, Deserialize, Debug)]
+#[empty(deserialize, fail_safe)]
+struct Database {
+    host: String,
+    timeout: u32,
+}
+
+#[derive(EmptyType, Deserialize, Debug)]
+#[empty(deserialize)]
+enum Transport {
+    Tcp {
+        #[empty(fail_safe)]
+        port: u16,
+    },
+}
+
+fn deserialize<T>(json: &str) -> Empty<T>
+where
+    T: EmptyType,
+    T::Container: for<'de> Deserialize<'de>,
+{
+    let mut de = serde_json::Deserializer::from_str(json);
+    deserialize_empty::<T, _>(&mut de).unwrap()
+}
+
+#[test]
+fn fallible_keeps_the_error_it_swallowed() {
+    let mut de = serde_json::Deserializer::from_str(r#""many""#);
+    let retries = Fallible::<Option<u8>>::deserialize(&mut de).unwrap();
+
+    assert!(retries.is_none());
+    let error = retries.error().unwrap();
+    assert!(error.path().is_empty());
+    assert!(error.message().contains("invalid type: string \"many\""));
+
+    let mut de = serde_json::Deserializer::from_str("3");
+    let retries = Fallible::<Option<u8>>::deserialize(&mut de).unwrap();
+    assert_eq!(*retries, Some(3));
+    assert!(retries.error().is_none());
+}
+
+#[test]
+fn swallowed_errors_are_collected_with_their_path() {
+    let config = deserialize::<Config>(
+        r#"{
+            "name": "service",
+            "retries": "many",
+            "database": { "host": "localhost", "timeout": -1 },
+            "ports": [80, "http", 443]
+        }"#,
+    );
+
+    let diagnostics = config.diagnostics();
+    let paths: Vec<_> = diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.path().to_string())
+        .collect();
+    assert_eq!(paths, vec!["retries", "database.timeout", "ports[1]"]);
+    assert_eq!(
+        diagnostics[2].path().segments(),
+        &[Field::Named("ports"), Field::Index(1)]
+    );
+    assert!(diagnostics[0]
+        .to_string()
+        .starts_with("`retries`: invalid type: string \"many\""));
+
+    let config = config.resolve();
+    assert_eq!(config.retries, 0);
+    assert_eq!(config.database.timeout, 0);
+    assert_eq!(config.ports, vec![80, 443]);
+}
+
+#[test]
+fn valid_input_has_no_diagnostics() {
+    let config = deserialize::<Config>(
+        r#"{
+            "name": "service",
+            "retries": 3,
+            "database": { "host": "localhost", "timeout": 30 },
+            "ports": [80]
+        }"#,
+    );
+
+    assert!(config.diagnostics().is_empty());
+}
+
+#[test]
+fn enum_diagnostics_are_named_after_the_variant() {
+    let transport = deserialize::<Transport>(r#"{ "Tcp": { "port": "http" } }"#);
+
+    let paths: Vec<_> = transport
+        .diagnostics()
+        .iter()
+        .map(|diagnostic| diagnostic.path().to_string())
+        .collect();
+    assert_eq!(paths, vec!["Tcp::port"]);
+}
+
+#[test]
+fn diagnostics_survive_merging_and_collections() {
+    let lower = deserialize::<Config>(r#"{ "retries": "many" }"#);
+    let higher = deserialize::<Config>(r#"{ "name": "service" }"#);
+
+    let merged = lower.overlay(higher);
+    assert_eq!(merged.diagnostics().len(), 1);
+
+    let higher = deserialize::<Config>(r#"{ "retries": 3 }"#);
+    let merged = merged.overlay(higher);
+    assert!(merged.diagnostics().is_empty());
+    assert_eq!(merged.resolve().retries, 3);
+
+    let configs = vec![
+        deserialize::<Config>(r#"{ "retries": 1 }"#),
+        deserialize::<Config>(r#"{ "retries": "many" }"#),
+    ];
+    let paths: Vec<_> = configs
+        .diagnostics()
+        .iter()
+        .map(|diagnostic| diagnostic.path().to_string())
+        .collect();
+    assert_eq!(paths, vec!["[1].retries"]);
+}
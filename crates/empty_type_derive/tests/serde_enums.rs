@@ -0,0 +1,194 @@
+/*
+ * Copyright [2022] [Kevin Velasco]
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use empty_type::{deserialize_empty, EmptyType};
+use empty_type_derive::EmptyType;
+use serde::Deserialize;
+
+#[derive(EmptyType, Deserialize, Debug, PartialEq)]
+#[empty(deserialize)]
+enum External {
+    Unit,
+    Newtype(u32),
+    Struct { key: String, value: u32 },
+}
+
+#[derive(EmptyType, Deserialize, Debug, PartialEq)]
+#[empty(deserialize)]
+#[serde(tag = "type")]
+enum Internal {
+    Unit,
+    Struct { key: String, value: u32 },
+}
+
+#[derive(EmptyType, Deserialize, Debug, PartialEq)]
+#[empty(deserialize)]
+#[serde(tag = "t", content = "c")]
+enum Adjacent {
+    Newtype(u32),
+    Struct { key: String, value: u32 },
+}
+
+#[derive(EmptyType, Deserialize, Debug, PartialEq)]
+#[empty(deserialize)]
+#[serde(untagged)]
+enum Untagged {
+    Struct { key: String, value: u32 },
+    Newtype(u32),
+}
+
+#[derive(EmptyType, Deserialize, Debug, PartialEq)]
+#[empty(deserialize)]
+#[serde(untagged)]
+enum Shape {
+    Circle {
+        radius: f64,
+    },
+    Rect {
+        w: f64,
+        h: f64,
+        label: Option<String>,
+    },
+}
+
+#[derive(EmptyType, Deserialize, Debug, PartialEq)]
+#[empty(deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Renamed {
+    #[serde(rename = "first")]
+    One {
+        key: String,
+    },
+    SecondVariant {
+        key: String,
+    },
+}
+
+fn deserialize<T>(json: &str) -> empty_type::Empty<T>
+where
+    T: EmptyType,
+    T::Container: for<'de> Deserialize<'de>,
+{
+    let mut de = serde_json::Deserializer::from_str(json);
+    deserialize_empty::<T, _>(&mut de).unwrap()
+}
+
+#[test]
+fn externally_tagged_enums_deserialize() {
+    assert_eq!(
+        deserialize::<External>(r#" "Unit" "#).resolve(),
+        External::Unit
+    );
+    assert_eq!(
+        deserialize::<External>(r#" { "Newtype": 1 } "#).resolve(),
+        External::Newtype(1)
+    );
+    assert_eq!(
+        deserialize::<External>(r#" { "Struct": { "key": "k", "value": 1 } } "#).resolve(),
+        External::Struct {
+            key: String::from("k"),
+            value: 1
+        }
+    );
+}
+
+#[test]
+fn partial_variants_report_missing_fields() {
+    let empty = deserialize::<External>(r#" { "Struct": { "key": "k" } } "#);
+    let error = empty.try_resolve().unwrap_err();
+
+    assert!(error.to_string().contains("Struct::value"), "{}", error);
+}
+
+#[test]
+fn internally_tagged_enums_deserialize() {
+    assert_eq!(
+        deserialize::<Internal>(r#" { "type": "Unit" } "#).resolve(),
+        Internal::Unit
+    );
+
+    let empty = deserialize::<Internal>(r#" { "type": "Struct", "value": 2 } "#);
+    assert!(empty.try_resolve().is_err());
+
+    assert_eq!(
+        deserialize::<Internal>(r#" { "type": "Struct", "key": "k", "value": 2 } "#).resolve(),
+        Internal::Struct {
+            key: String::from("k"),
+            value: 2
+        }
+    );
+}
+
+#[test]
+fn adjacently_tagged_enums_deserialize() {
+    assert_eq!(
+        deserialize::<Adjacent>(r#" { "t": "Newtype", "c": 3 } "#).resolve(),
+        Adjacent::Newtype(3)
+    );
+
+    let empty = deserialize::<Adjacent>(r#" { "t": "Struct", "c": { "key": "k" } } "#);
+    assert!(empty.try_resolve().is_err());
+}
+
+#[test]
+fn untagged_enums_deserialize() {
+    assert_eq!(
+        deserialize::<Untagged>(r#" 4 "#).resolve(),
+        Untagged::Newtype(4)
+    );
+    assert_eq!(
+        deserialize::<Untagged>(r#" { "key": "k", "value": 4 } "#).resolve(),
+        Untagged::Struct {
+            key: String::from("k"),
+            value: 4
+        }
+    );
+}
+
+#[test]
+fn untagged_variants_are_told_apart_by_their_fields() {
+    assert_eq!(
+        deserialize::<Shape>(r#" { "w": 1.0, "h": 2.0 } "#).resolve(),
+        Shape::Rect {
+            w: 1.0,
+            h: 2.0,
+            label: None
+        }
+    );
+    assert_eq!(
+        deserialize::<Shape>(r#" { "radius": 1.0 } "#).resolve(),
+        Shape::Circle { radius: 1.0 }
+    );
+
+    let shape = serde_json::from_str::<empty_type::Empty<Shape>>(r#" { "w": 1.0 } "#);
+    assert!(shape.is_err());
+}
+
+#[test]
+fn variant_renames_are_respected() {
+    assert_eq!(
+        deserialize::<Renamed>(r#" { "first": { "key": "k" } } "#).resolve(),
+        Renamed::One {
+            key: String::from("k")
+        }
+    );
+    assert_eq!(
+        deserialize::<Renamed>(r#" { "second_variant": { "key": "k" } } "#).resolve(),
+        Renamed::SecondVariant {
+            key: String::from("k")
+        }
+    );
+}
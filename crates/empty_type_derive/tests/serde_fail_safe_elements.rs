@@ -0,0 +1,196 @@
+/*
+ * Copyright [2022] [Kevin Velasco]
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use empty_type::{deserialize_empty, Container, EmptyType};
+use empty_type_derive::EmptyType;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashSet};
+
+#[derive(EmptyType, Deserialize, Debug)]
+#[empty(deserialize)]
+struct Inventory {
+    #[empty(fail_safe = "elements")]
+    counts: Vec<u32>,
+    #[empty(fail_safe = "elements")]
+    items: Vec<Item>,
+    #[empty(fail_safe = "elements")]
+    prices: BTreeMap<String, f64>,
+    #[empty(fail_safe = "elements")]
+    tags: HashSet<String>,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Item {
+    name: String,
+    quantity: u32,
+}
+
+#[derive(EmptyType, Deserialize, Debug)]
+#[empty(deserialize, fail_safe)]
+struct Layered {
+    #[empty(fail_safe = "elements")]
+    counts: Vec<u32>,
+}
+
+#[derive(EmptyType, Deserialize, Debug)]
+#[empty(deserialize)]
+struct Readings {
+    #[empty(fail_safe = "elements")]
+    values: Vec<Option<u32>>,
+}
+
+fn deserialize<T>(json: &str) -> empty_type::Empty<T>
+where
+    T: EmptyType,
+    T::Container: for<'de> Deserialize<'de>,
+{
+    let mut de = serde_json::Deserializer::from_str(json);
+    deserialize_empty::<T, _>(&mut de).unwrap()
+}
+
+#[test]
+fn bad_elements_are_dropped() {
+    let inventory = deserialize::<Inventory>(
+        r#"{
+            "counts": [1, "two", 3, null, 4],
+            "items": [
+                { "name": "bolt", "quantity": 10 },
+                { "name": "nut" },
+                "washer",
+                { "name": "screw", "quantity": 5 }
+            ],
+            "prices": { "bolt": 0.5, "nut": "cheap" },
+            "tags": ["metal", 7, "small"]
+        }"#,
+    )
+    .resolve();
+
+    assert_eq!(inventory.counts, vec![1, 3, 4]);
+    assert_eq!(
+        inventory.items,
+        vec![
+            Item {
+                name: "bolt".to_owned(),
+                quantity: 10
+            },
+            Item {
+                name: "screw".to_owned(),
+                quantity: 5
+            }
+        ]
+    );
+    assert_eq!(inventory.prices.len(), 1);
+    assert_eq!(inventory.prices["bolt"], 0.5);
+    assert_eq!(
+        inventory.tags,
+        ["metal", "small"]
+            .iter()
+            .map(|tag| tag.to_string())
+            .collect()
+    );
+}
+
+#[test]
+fn every_dropped_element_has_a_diagnostic() {
+    let inventory = deserialize::<Inventory>(r#"{ "counts": [1, null, "x", 3] }"#);
+
+    let paths: Vec<_> = inventory
+        .diagnostics()
+        .iter()
+        .map(|diagnostic| diagnostic.path().to_string())
+        .collect();
+    assert_eq!(paths, vec!["counts[1]", "counts[2]"]);
+    assert_eq!(inventory.resolve().counts, vec![1, 3]);
+}
+
+#[test]
+fn null_elements_of_options_are_kept() {
+    let empty = deserialize::<Readings>(r#"{ "values": [1, null, "x"] }"#);
+
+    assert_eq!(empty.diagnostics().len(), 1);
+    assert_eq!(empty.resolve().values, vec![Some(1), None]);
+}
+
+#[test]
+fn missing_collections_resolve_to_their_default() {
+    let mut empty = deserialize::<Inventory>(r#"{ "counts": [1] }"#);
+
+    assert!(empty.items.is_unset());
+    let inventory = empty.open();
+
+    assert_eq!(inventory.counts, vec![1]);
+    assert!(inventory.items.is_empty());
+    assert!(inventory.prices.is_empty());
+}
+
+#[test]
+fn a_collection_of_the_wrong_type_is_unset_with_a_diagnostic() {
+    let mut empty = deserialize::<Inventory>(r#"{ "counts": "oops", "items": [] }"#);
+
+    assert!(empty.counts.is_unset());
+    let paths: Vec<_> = empty
+        .diagnostics()
+        .iter()
+        .map(|diagnostic| diagnostic.path().to_string())
+        .collect();
+    assert_eq!(paths, vec!["counts"]);
+
+    let inventory = empty.open();
+    assert!(inventory.counts.is_empty());
+    assert!(inventory.items.is_empty());
+
+    let layered = deserialize::<Layered>(r#"{ "counts": "none" }"#).resolve();
+    assert!(layered.counts.is_empty());
+
+    let layered = deserialize::<Layered>(r#"{ "counts": [1, -2, 3] }"#).resolve();
+    assert_eq!(layered.counts, vec![1, 3]);
+}
+
+#[test]
+fn a_null_collection_is_unset_with_a_diagnostic() {
+    let empty = deserialize::<Inventory>(r#"{ "counts": null, "tags": ["a"] }"#);
+
+    assert!(empty.counts.is_unset());
+    assert_eq!(empty.diagnostics().len(), 1);
+    assert_eq!(empty.diagnostics()[0].path().to_string(), "counts");
+
+    let inventory = empty.resolve();
+    assert!(inventory.counts.is_empty());
+    assert_eq!(inventory.tags.len(), 1);
+}
+
+#[test]
+fn a_missing_collection_has_no_diagnostic() {
+    let empty = deserialize::<Inventory>(r#"{}"#);
+
+    assert!(empty.counts.is_unset());
+    assert!(empty.diagnostics().is_empty());
+    assert!(empty.resolve().counts.is_empty());
+}
+
+#[test]
+fn full_values_convert_into_set_collections() {
+    let inventory = Inventory {
+        counts: vec![1, 2],
+        items: vec![],
+        prices: BTreeMap::new(),
+        tags: HashSet::new(),
+    };
+
+    let empty = inventory.into_empty();
+
+    assert_eq!(empty.counts.as_deref(), Some(&[1, 2][..]));
+}
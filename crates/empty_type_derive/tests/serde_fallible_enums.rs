@@ -0,0 +1,240 @@
+/*
+ * Copyright [2022] [Kevin Velasco]
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use empty_type::{deserialize_empty, Fallible};
+use empty_type_derive::EmptyType;
+use serde::de::value::{EnumAccessDeserializer, MapAccessDeserializer, MapDeserializer};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+#[derive(Deserialize, Debug, PartialEq, Default)]
+enum External {
+    #[default]
+    Unit,
+    Newtype(u32),
+    Tuple(u32, String),
+    Struct {
+        key: String,
+        value: u32,
+    },
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(tag = "type")]
+enum Internal {
+    Unit,
+    Newtype(Payload),
+    Struct { key: String, value: u32 },
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(tag = "t", content = "c")]
+enum Adjacent {
+    Unit,
+    Newtype(u32),
+    Tuple(u32, String),
+    Struct { key: String, value: u32 },
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(untagged)]
+enum Untagged {
+    Struct { key: String, value: u32 },
+    Tuple(u32, String),
+    Newtype(u32),
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Payload {
+    key: String,
+}
+
+#[derive(EmptyType, Deserialize)]
+#[empty(deserialize)]
+struct Connection {
+    #[empty(fail_safe)]
+    external: External,
+    #[empty(fail_safe)]
+    adjacent: Option<Adjacent>,
+}
+
+fn from_json<T>(value: Value) -> Fallible<Option<T>>
+where
+    T: for<'de> Deserialize<'de>,
+{
+    let fallible = Fallible::<Option<T>>::deserialize(&value).unwrap();
+    assert!(
+        fallible.error().is_none(),
+        "{} fell back: {}",
+        value,
+        fallible.error().unwrap()
+    );
+    fallible
+}
+
+// Replays `{ variant: payload }` through `visit_enum`, the way formats with
+// native enums hand them to `deserialize_any`
+fn from_enum_access<T>(variant: &'static str, payload: Value) -> Fallible<Option<T>>
+where
+    T: for<'de> Deserialize<'de>,
+{
+    let map = MapDeserializer::<_, serde_json::Error>::new(vec![(variant, payload)].into_iter());
+    let deserializer = EnumAccessDeserializer::new(MapAccessDeserializer::new(map));
+    Fallible::<Option<T>>::deserialize(deserializer).unwrap()
+}
+
+#[test]
+fn externally_tagged_payloads_are_kept() {
+    assert_eq!(*from_json::<External>(json!("Unit")), Some(External::Unit));
+    assert_eq!(
+        *from_json::<External>(json!({ "Newtype": 1 })),
+        Some(External::Newtype(1))
+    );
+    assert_eq!(
+        *from_json::<External>(json!({ "Tuple": [1, "a"] })),
+        Some(External::Tuple(1, "a".to_owned()))
+    );
+    assert_eq!(
+        *from_json::<External>(json!({ "Struct": { "key": "a", "value": 1 } })),
+        Some(External::Struct {
+            key: "a".to_owned(),
+            value: 1
+        })
+    );
+}
+
+#[test]
+fn internally_tagged_payloads_are_kept() {
+    assert_eq!(
+        *from_json::<Internal>(json!({ "type": "Unit" })),
+        Some(Internal::Unit)
+    );
+    assert_eq!(
+        *from_json::<Internal>(json!({ "type": "Newtype", "key": "a" })),
+        Some(Internal::Newtype(Payload {
+            key: "a".to_owned()
+        }))
+    );
+    assert_eq!(
+        *from_json::<Internal>(json!({ "type": "Struct", "key": "a", "value": 1 })),
+        Some(Internal::Struct {
+            key: "a".to_owned(),
+            value: 1
+        })
+    );
+}
+
+#[test]
+fn adjacently_tagged_payloads_are_kept() {
+    assert_eq!(
+        *from_json::<Adjacent>(json!({ "t": "Unit" })),
+        Some(Adjacent::Unit)
+    );
+    assert_eq!(
+        *from_json::<Adjacent>(json!({ "t": "Newtype", "c": 1 })),
+        Some(Adjacent::Newtype(1))
+    );
+    assert_eq!(
+        *from_json::<Adjacent>(json!({ "t": "Tuple", "c": [1, "a"] })),
+        Some(Adjacent::Tuple(1, "a".to_owned()))
+    );
+    assert_eq!(
+        *from_json::<Adjacent>(json!({ "t": "Struct", "c": { "key": "a", "value": 1 } })),
+        Some(Adjacent::Struct {
+            key: "a".to_owned(),
+            value: 1
+        })
+    );
+}
+
+#[test]
+fn untagged_payloads_are_kept() {
+    assert_eq!(*from_json::<Untagged>(json!(1)), Some(Untagged::Newtype(1)));
+    assert_eq!(
+        *from_json::<Untagged>(json!([1, "a"])),
+        Some(Untagged::Tuple(1, "a".to_owned()))
+    );
+    assert_eq!(
+        *from_json::<Untagged>(json!({ "key": "a", "value": 1 })),
+        Some(Untagged::Struct {
+            key: "a".to_owned(),
+            value: 1
+        })
+    );
+}
+
+#[test]
+fn native_enum_payloads_are_replayed() {
+    let unit = from_enum_access::<External>("Unit", Value::Null);
+    assert_eq!(*unit, Some(External::Unit));
+
+    let newtype = from_enum_access::<External>("Newtype", json!(1));
+    assert_eq!(*newtype, Some(External::Newtype(1)));
+
+    let tuple = from_enum_access::<External>("Tuple", json!([1, "a"]));
+    assert_eq!(*tuple, Some(External::Tuple(1, "a".to_owned())));
+
+    let strukt = from_enum_access::<External>("Struct", json!({ "key": "a", "value": 1 }));
+    assert_eq!(
+        *strukt,
+        Some(External::Struct {
+            key: "a".to_owned(),
+            value: 1
+        })
+    );
+}
+
+#[test]
+fn malformed_payloads_fall_back_to_the_default() {
+    let newtype = from_enum_access::<External>("Newtype", json!("one"));
+    assert_eq!(*newtype, None);
+    assert!(newtype.error().is_some());
+
+    let unknown = from_enum_access::<External>("Missing", Value::Null);
+    assert_eq!(*unknown, None);
+    assert!(unknown.error().is_some());
+}
+
+#[test]
+fn fail_safe_enum_fields_keep_their_payload() {
+    let json = r#"{
+        "external": { "Struct": { "key": "a", "value": 1 } },
+        "adjacent": { "t": "Tuple", "c": [1, "a"] }
+    }"#;
+    let mut de = serde_json::Deserializer::from_str(json);
+    let connection = deserialize_empty::<Connection, _>(&mut de)
+        .unwrap()
+        .resolve();
+
+    assert_eq!(
+        connection.external,
+        External::Struct {
+            key: "a".to_owned(),
+            value: 1
+        }
+    );
+    assert_eq!(
+        connection.adjacent,
+        Some(Adjacent::Tuple(1, "a".to_owned()))
+    );
+
+    let mut de = serde_json::Deserializer::from_str(r#"{ "external": { "Newtype": "one" } }"#);
+    let connection = deserialize_empty::<Connection, _>(&mut de)
+        .unwrap()
+        .resolve();
+
+    assert_eq!(connection.external, External::Unit);
+}
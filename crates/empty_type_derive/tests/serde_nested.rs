@@ -0,0 +1,119 @@
+/*
+ * Copyright [2022] [Kevin Velasco]
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use empty_type::deserialize_empty;
+use empty_type_derive::EmptyType;
+use serde::Deserialize;
+
+#[derive(EmptyType, Deserialize, Debug, PartialEq)]
+#[empty(deserialize)]
+struct Config {
+    name: String,
+    #[empty(nested)]
+    database: Database,
+}
+
+#[derive(EmptyType, Deserialize, Debug, PartialEq)]
+#[empty(deserialize)]
+struct Database {
+    host: String,
+    port: u16,
+}
+
+#[derive(EmptyType, Deserialize, Debug, PartialEq)]
+#[empty(deserialize)]
+struct Cluster {
+    #[empty(nested)]
+    replicas: Vec<Database>,
+}
+
+#[derive(EmptyType, Deserialize, Debug, PartialEq)]
+#[empty(deserialize)]
+struct FailSafe {
+    #[empty(nested, fail_safe)]
+    database: Defaulted,
+}
+
+#[derive(EmptyType, Deserialize, Debug, PartialEq, Default)]
+#[empty(deserialize, fail_safe)]
+struct Defaulted {
+    port: u16,
+}
+
+#[test]
+fn partial_nested_objects_are_kept() {
+    let json = r#" { "name": "service", "database": { "host": "localhost" } } "#;
+
+    let mut de = serde_json::Deserializer::from_str(json);
+    let value = deserialize_empty::<Config, _>(&mut de).unwrap();
+
+    assert_eq!(value.database.host.as_deref(), Some("localhost"));
+    assert!(value.database.port.is_none());
+
+    let error = value.try_resolve().unwrap_err().to_string();
+    assert!(error.contains("`database.port`"), "{}", error);
+}
+
+#[test]
+fn missing_nested_objects_are_empty() {
+    let json = r#" { "name": "service" } "#;
+
+    let mut de = serde_json::Deserializer::from_str(json);
+    let value = deserialize_empty::<Config, _>(&mut de).unwrap();
+
+    assert!(value.database.host.is_none());
+}
+
+#[test]
+fn complete_nested_objects_resolve() {
+    let json = r#" { "name": "service", "database": { "host": "localhost", "port": 1 } } "#;
+
+    let mut de = serde_json::Deserializer::from_str(json);
+    let value = deserialize_empty::<Config, _>(&mut de).unwrap();
+
+    assert_eq!(
+        value.resolve(),
+        Config {
+            name: String::from("service"),
+            database: Database {
+                host: String::from("localhost"),
+                port: 1
+            }
+        }
+    );
+}
+
+#[test]
+fn fail_safe_nested_objects_resolve_to_default() {
+    let json = r#" { "database": { "port": "not a number" } } "#;
+
+    let mut de = serde_json::Deserializer::from_str(json);
+    let value = deserialize_empty::<FailSafe, _>(&mut de).unwrap();
+
+    assert_eq!(value.resolve().database, Defaulted { port: 0 });
+}
+
+#[test]
+fn partial_elements_of_nested_collections_are_kept() {
+    let json = r#" { "replicas": [{ "host": "a", "port": 1 }, { "host": "b" }] } "#;
+
+    let mut de = serde_json::Deserializer::from_str(json);
+    let value = deserialize_empty::<Cluster, _>(&mut de).unwrap();
+
+    assert_eq!(value.replicas[1].host.as_deref(), Some("b"));
+    let error = value.try_resolve().unwrap_err().to_string();
+    assert!(error.contains("`replicas[1].port`"), "{}", error);
+}
@@ -0,0 +1,514 @@
+/*
+ * Copyright [2022] [Kevin Velasco]
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use empty_type::{Container, DeserializeHinted, Fallible};
+use empty_type_derive::EmptyType;
+use serde::Deserialize;
+
+// A minimal binary format in the style of bincode. Values are read by the
+// hint of the type being deserialized, so `deserialize_any` is rejected.
+mod binary {
+    use serde::de::{
+        self, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess, VariantAccess,
+        Visitor,
+    };
+    use std::fmt::{self, Display};
+
+    #[derive(Debug)]
+    pub struct Error(String);
+
+    impl Display for Error {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(&self.0)
+        }
+    }
+
+    impl std::error::Error for Error {}
+
+    impl de::Error for Error {
+        fn custom<T: Display>(message: T) -> Self {
+            Error(message.to_string())
+        }
+    }
+
+    pub struct Reader<'de> {
+        bytes: &'de [u8],
+    }
+
+    pub fn from_bytes<'de, T: de::Deserialize<'de>>(bytes: &'de [u8]) -> Result<T, Error> {
+        read_bytes(bytes, |reader| T::deserialize(reader))
+    }
+
+    /// Reads all of `bytes` with `read`
+    pub fn read_bytes<'de, T>(
+        bytes: &'de [u8],
+        read: impl FnOnce(&mut Reader<'de>) -> Result<T, Error>,
+    ) -> Result<T, Error> {
+        let mut reader = Reader { bytes };
+        let value = read(&mut reader)?;
+        if reader.bytes.is_empty() {
+            Ok(value)
+        } else {
+            Err(Error(format!("{} trailing bytes", reader.bytes.len())))
+        }
+    }
+
+    impl<'de> Reader<'de> {
+        fn take(&mut self, len: usize) -> Result<&'de [u8], Error> {
+            if self.bytes.len() < len {
+                return Err(Error("unexpected end of input".to_owned()));
+            }
+            let (taken, rest) = self.bytes.split_at(len);
+            self.bytes = rest;
+            Ok(taken)
+        }
+
+        fn u8(&mut self) -> Result<u8, Error> {
+            Ok(self.take(1)?[0])
+        }
+
+        fn u32(&mut self) -> Result<u32, Error> {
+            let mut bytes = [0; 4];
+            bytes.copy_from_slice(self.take(4)?);
+            Ok(u32::from_le_bytes(bytes))
+        }
+
+        fn u64(&mut self) -> Result<u64, Error> {
+            let mut bytes = [0; 8];
+            bytes.copy_from_slice(self.take(8)?);
+            Ok(u64::from_le_bytes(bytes))
+        }
+
+        fn len(&mut self) -> Result<usize, Error> {
+            Ok(self.u32()? as usize)
+        }
+    }
+
+    impl<'de> de::Deserializer<'de> for &mut Reader<'de> {
+        type Error = Error;
+
+        fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Error> {
+            Err(Error("the format is not self-describing".to_owned()))
+        }
+
+        fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            match self.u8()? {
+                0 => visitor.visit_bool(false),
+                1 => visitor.visit_bool(true),
+                byte => Err(Error(format!("invalid bool {}", byte))),
+            }
+        }
+
+        fn deserialize_i8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            visitor.visit_i8(self.u8()? as i8)
+        }
+
+        fn deserialize_i16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            visitor.visit_i16(self.u32()? as i16)
+        }
+
+        fn deserialize_i32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            visitor.visit_i32(self.u32()? as i32)
+        }
+
+        fn deserialize_i64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            visitor.visit_i64(self.u64()? as i64)
+        }
+
+        fn deserialize_u8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            visitor.visit_u8(self.u8()?)
+        }
+
+        fn deserialize_u16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            visitor.visit_u16(self.u32()? as u16)
+        }
+
+        fn deserialize_u32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            visitor.visit_u32(self.u32()?)
+        }
+
+        fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            visitor.visit_u64(self.u64()?)
+        }
+
+        fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            visitor.visit_f32(f32::from_bits(self.u32()?))
+        }
+
+        fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            visitor.visit_f64(f64::from_bits(self.u64()?))
+        }
+
+        fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            let code = self.u32()?;
+            match char::from_u32(code) {
+                Some(char) => visitor.visit_char(char),
+                None => Err(Error(format!("invalid char {}", code))),
+            }
+        }
+
+        fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            let len = self.len()?;
+            match std::str::from_utf8(self.take(len)?) {
+                Ok(str) => visitor.visit_borrowed_str(str),
+                Err(error) => Err(Error(error.to_string())),
+            }
+        }
+
+        fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            self.deserialize_str(visitor)
+        }
+
+        fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            let len = self.len()?;
+            visitor.visit_borrowed_bytes(self.take(len)?)
+        }
+
+        fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            self.deserialize_bytes(visitor)
+        }
+
+        fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            match self.u8()? {
+                0 => visitor.visit_none(),
+                1 => visitor.visit_some(self),
+                byte => Err(Error(format!("invalid option tag {}", byte))),
+            }
+        }
+
+        fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            visitor.visit_unit()
+        }
+
+        fn deserialize_unit_struct<V: Visitor<'de>>(
+            self,
+            _: &'static str,
+            visitor: V,
+        ) -> Result<V::Value, Error> {
+            visitor.visit_unit()
+        }
+
+        fn deserialize_newtype_struct<V: Visitor<'de>>(
+            self,
+            _: &'static str,
+            visitor: V,
+        ) -> Result<V::Value, Error> {
+            visitor.visit_newtype_struct(self)
+        }
+
+        fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            let len = self.len()?;
+            visitor.visit_seq(Elements { reader: self, len })
+        }
+
+        fn deserialize_tuple<V: Visitor<'de>>(
+            self,
+            len: usize,
+            visitor: V,
+        ) -> Result<V::Value, Error> {
+            visitor.visit_seq(Elements { reader: self, len })
+        }
+
+        fn deserialize_tuple_struct<V: Visitor<'de>>(
+            self,
+            _: &'static str,
+            len: usize,
+            visitor: V,
+        ) -> Result<V::Value, Error> {
+            self.deserialize_tuple(len, visitor)
+        }
+
+        fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            let len = self.len()?;
+            visitor.visit_map(Elements { reader: self, len })
+        }
+
+        fn deserialize_struct<V: Visitor<'de>>(
+            self,
+            _: &'static str,
+            fields: &'static [&'static str],
+            visitor: V,
+        ) -> Result<V::Value, Error> {
+            self.deserialize_tuple(fields.len(), visitor)
+        }
+
+        fn deserialize_enum<V: Visitor<'de>>(
+            self,
+            _: &'static str,
+            _: &'static [&'static str],
+            visitor: V,
+        ) -> Result<V::Value, Error> {
+            visitor.visit_enum(self)
+        }
+
+        fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            self.deserialize_u32(visitor)
+        }
+
+        fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            self.deserialize_any(visitor)
+        }
+
+        fn is_human_readable(&self) -> bool {
+            false
+        }
+    }
+
+    struct Elements<'a, 'de> {
+        reader: &'a mut Reader<'de>,
+        len: usize,
+    }
+
+    impl<'de, 'a> SeqAccess<'de> for Elements<'a, 'de> {
+        type Error = Error;
+
+        fn next_element_seed<T: DeserializeSeed<'de>>(
+            &mut self,
+            seed: T,
+        ) -> Result<Option<T::Value>, Error> {
+            if self.len == 0 {
+                return Ok(None);
+            }
+            self.len -= 1;
+            seed.deserialize(&mut *self.reader).map(Some)
+        }
+    }
+
+    impl<'de, 'a> MapAccess<'de> for Elements<'a, 'de> {
+        type Error = Error;
+
+        fn next_key_seed<K: DeserializeSeed<'de>>(
+            &mut self,
+            seed: K,
+        ) -> Result<Option<K::Value>, Error> {
+            self.next_element_seed(seed)
+        }
+
+        fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+            seed.deserialize(&mut *self.reader)
+        }
+    }
+
+    impl<'de> EnumAccess<'de> for &mut Reader<'de> {
+        type Error = Error;
+        type Variant = Self;
+
+        fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self), Error> {
+            let index = self.u32()?;
+            let variant = seed.deserialize(index.into_deserializer())?;
+            Ok((variant, self))
+        }
+    }
+
+    impl<'de> VariantAccess<'de> for &mut Reader<'de> {
+        type Error = Error;
+
+        fn unit_variant(self) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+            seed.deserialize(self)
+        }
+
+        fn tuple_variant<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, Error> {
+            de::Deserializer::deserialize_tuple(self, len, visitor)
+        }
+
+        fn struct_variant<V: Visitor<'de>>(
+            self,
+            fields: &'static [&'static str],
+            visitor: V,
+        ) -> Result<V::Value, Error> {
+            de::Deserializer::deserialize_tuple(self, fields.len(), visitor)
+        }
+    }
+
+    /// Writes values the way [`Reader`] reads them
+    #[derive(Default)]
+    pub struct Writer(pub Vec<u8>);
+
+    impl Writer {
+        pub fn u8(mut self, value: u8) -> Self {
+            self.0.push(value);
+            self
+        }
+
+        pub fn u32(mut self, value: u32) -> Self {
+            self.0.extend_from_slice(&value.to_le_bytes());
+            self
+        }
+
+        pub fn bytes(self, value: &[u8]) -> Self {
+            let mut writer = self.u32(value.len() as u32);
+            writer.0.extend_from_slice(value);
+            writer
+        }
+
+        pub fn some(self) -> Self {
+            self.u8(1)
+        }
+    }
+}
+
+use binary::{from_bytes, read_bytes, Writer};
+
+#[derive(Deserialize, Debug, PartialEq, Default)]
+enum Kind {
+    #[default]
+    Ping,
+    Data(u32),
+    Named {
+        name: String,
+    },
+}
+
+#[derive(EmptyType, Deserialize, Debug)]
+#[empty(deserialize, fail_safe, hint)]
+struct Message {
+    id: u32,
+    urgent: bool,
+    name: String,
+    kind: Kind,
+    port: Option<u32>,
+}
+
+#[derive(EmptyType, Deserialize, Debug)]
+#[empty(deserialize)]
+struct Batch {
+    id: u32,
+    #[empty(fail_safe = "elements", hint)]
+    names: Vec<String>,
+    #[empty(fail_safe, hint)]
+    last: String,
+}
+
+// The fields of a `Message` container, each set
+fn message(urgent: u8, name: &[u8], kind: Writer) -> Vec<u8> {
+    let writer = Writer::default()
+        .some()
+        .u32(7)
+        .some()
+        .u8(urgent)
+        .some()
+        .bytes(name);
+    let mut bytes = writer.0;
+    bytes.extend(kind.0);
+    bytes.extend(Writer::default().some().u32(8080).0);
+    bytes
+}
+
+#[test]
+fn the_test_format_is_not_self_describing() {
+    let bytes = Writer::default().u32(1).0;
+    let error = from_bytes::<serde::de::IgnoredAny>(&bytes).unwrap_err();
+
+    assert_eq!(error.to_string(), "the format is not self-describing");
+}
+
+#[test]
+fn fail_safe_fields_deserialize_by_their_hint() {
+    let kind = Writer::default().some().u32(2).bytes(b"stream");
+    let bytes = message(1, b"service", kind);
+
+    let container: empty_type::Empty<Message> = from_bytes(&bytes).unwrap();
+    assert!(container.diagnostics().is_empty());
+
+    let message = container.resolve();
+    assert_eq!(message.id, 7);
+    assert!(message.urgent);
+    assert_eq!(message.name, "service");
+    assert_eq!(
+        message.kind,
+        Kind::Named {
+            name: "stream".to_owned()
+        }
+    );
+    assert_eq!(message.port, Some(8080));
+}
+
+#[test]
+fn values_which_were_read_whole_fall_back_to_their_default() {
+    // an invalid bool, a name which isn't UTF-8 and a variant which doesn't exist
+    let kind = Writer::default().some().u32(9);
+    let bytes = message(7, &[0xff, 0xfe], kind);
+
+    let container: empty_type::Empty<Message> = from_bytes(&bytes).unwrap();
+    let paths: Vec<_> = container
+        .diagnostics()
+        .iter()
+        .map(|diagnostic| diagnostic.path().to_string())
+        .collect();
+    assert_eq!(paths, vec!["urgent", "name", "kind"]);
+
+    // the fields after the ones that failed are still read
+    let message = container.resolve();
+    assert_eq!(message.id, 7);
+    assert!(!message.urgent);
+    assert_eq!(message.name, "");
+    assert_eq!(message.kind, Kind::Ping);
+    assert_eq!(message.port, Some(8080));
+}
+
+#[test]
+fn fallible_values_keep_their_error() {
+    let bytes = Writer::default().some().u32(1).u32(5).0;
+    let kind = read_bytes(&bytes, |reader| {
+        Fallible::<Option<Kind>>::deserialize_hinted(reader)
+    })
+    .unwrap();
+    assert_eq!(*kind, Some(Kind::Data(5)));
+
+    let bytes = Writer::default().u8(3).0;
+    let kind = read_bytes(&bytes, |reader| {
+        Fallible::<Option<Kind>>::deserialize_hinted(reader)
+    })
+    .unwrap();
+    assert_eq!(*kind, None);
+    assert_eq!(kind.error().unwrap().message(), "invalid option tag 3");
+}
+
+#[test]
+fn fallible_values_are_read_with_deserialize_any_without_a_hint() {
+    let kind = read_bytes(&[], |reader| Fallible::<Option<Kind>>::deserialize(reader)).unwrap();
+    assert_eq!(*kind, None);
+    assert_eq!(
+        kind.error().unwrap().message(),
+        "the format is not self-describing"
+    );
+}
+
+#[test]
+fn elements_which_were_read_whole_are_dropped() {
+    let writer = Writer::default()
+        .some()
+        .u32(1)
+        .u32(3)
+        .bytes(b"first")
+        .bytes(&[0xff])
+        .bytes(b"third")
+        .some()
+        .bytes(b"last");
+
+    let mut batch: empty_type::Empty<Batch> = from_bytes(&writer.0).unwrap();
+    let diagnostics = batch.diagnostics();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].path().to_string(), "names[1]");
+
+    let batch = batch.open();
+    assert_eq!(batch.names, vec!["first", "third"]);
+    assert_eq!(batch.last, "last");
+}
@@ -0,0 +1,318 @@
+/*
+ * Copyright [2022] [Kevin Velasco]
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use empty_type::{Container, Empty};
+use empty_type_derive::EmptyType;
+use serde::Deserialize;
+
+// A minimal binary format in the style of CBOR or MessagePack. Every value is
+// tagged, so `deserialize_any` works, but it isn't human readable. A value of
+// the wrong type is rejected by its tag before its payload is read, like a
+// streaming reader would.
+mod tagged {
+    use serde::de::{self, DeserializeSeed, MapAccess, SeqAccess, Visitor};
+    use serde::forward_to_deserialize_any;
+    use std::fmt::{self, Display};
+
+    const UNIT: u8 = 0;
+    const INTEGER: u8 = 1;
+    const STRING: u8 = 2;
+    const SEQ: u8 = 3;
+    const MAP: u8 = 4;
+
+    #[derive(Debug)]
+    pub struct Error(String);
+
+    impl Display for Error {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(&self.0)
+        }
+    }
+
+    impl std::error::Error for Error {}
+
+    impl de::Error for Error {
+        fn custom<T: Display>(message: T) -> Self {
+            Error(message.to_string())
+        }
+    }
+
+    pub struct Reader<'de> {
+        bytes: &'de [u8],
+    }
+
+    pub fn from_bytes<'de, T: de::Deserialize<'de>>(bytes: &'de [u8]) -> Result<T, Error> {
+        let mut reader = Reader { bytes };
+        let value = T::deserialize(&mut reader)?;
+        if reader.bytes.is_empty() {
+            Ok(value)
+        } else {
+            Err(Error(format!("{} trailing bytes", reader.bytes.len())))
+        }
+    }
+
+    impl<'de> Reader<'de> {
+        fn take(&mut self, len: usize) -> Result<&'de [u8], Error> {
+            if self.bytes.len() < len {
+                return Err(Error("unexpected end of input".to_owned()));
+            }
+            let (taken, rest) = self.bytes.split_at(len);
+            self.bytes = rest;
+            Ok(taken)
+        }
+
+        fn tag(&mut self) -> Result<u8, Error> {
+            Ok(self.take(1)?[0])
+        }
+
+        fn u32(&mut self) -> Result<u32, Error> {
+            let mut bytes = [0; 4];
+            bytes.copy_from_slice(self.take(4)?);
+            Ok(u32::from_le_bytes(bytes))
+        }
+
+        fn u64(&mut self) -> Result<u64, Error> {
+            let mut bytes = [0; 8];
+            bytes.copy_from_slice(self.take(8)?);
+            Ok(u64::from_le_bytes(bytes))
+        }
+
+        fn len(&mut self) -> Result<usize, Error> {
+            Ok(self.u32()? as usize)
+        }
+    }
+
+    impl<'de> de::Deserializer<'de> for &mut Reader<'de> {
+        type Error = Error;
+
+        fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            match self.tag()? {
+                UNIT => visitor.visit_unit(),
+                INTEGER => visitor.visit_u64(self.u64()?),
+                STRING => {
+                    let len = self.len()?;
+                    match std::str::from_utf8(self.take(len)?) {
+                        Ok(str) => visitor.visit_borrowed_str(str),
+                        Err(error) => Err(Error(error.to_string())),
+                    }
+                }
+                SEQ => {
+                    let len = self.len()?;
+                    visitor.visit_seq(Elements { reader: self, len })
+                }
+                MAP => {
+                    let len = self.len()?;
+                    visitor.visit_map(Elements { reader: self, len })
+                }
+                tag => Err(Error(format!("invalid tag {}", tag))),
+            }
+        }
+
+        fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            match self.tag()? {
+                INTEGER => visitor.visit_u64(self.u64()?),
+                tag => Err(Error(format!("expected an integer, found tag {}", tag))),
+            }
+        }
+
+        fn deserialize_u16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            self.deserialize_u64(visitor)
+        }
+
+        fn deserialize_u32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            self.deserialize_u64(visitor)
+        }
+
+        fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            if self.bytes.first() == Some(&UNIT) {
+                self.tag()?;
+                visitor.visit_none()
+            } else {
+                visitor.visit_some(self)
+            }
+        }
+
+        fn is_human_readable(&self) -> bool {
+            false
+        }
+
+        forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u128 f32 f64 char str string bytes byte_buf unit
+            unit_struct newtype_struct seq tuple tuple_struct map struct enum identifier
+            ignored_any
+        }
+    }
+
+    struct Elements<'a, 'de> {
+        reader: &'a mut Reader<'de>,
+        len: usize,
+    }
+
+    impl<'de, 'a> SeqAccess<'de> for Elements<'a, 'de> {
+        type Error = Error;
+
+        fn next_element_seed<T: DeserializeSeed<'de>>(
+            &mut self,
+            seed: T,
+        ) -> Result<Option<T::Value>, Error> {
+            if self.len == 0 {
+                return Ok(None);
+            }
+            self.len -= 1;
+            seed.deserialize(&mut *self.reader).map(Some)
+        }
+    }
+
+    impl<'de, 'a> MapAccess<'de> for Elements<'a, 'de> {
+        type Error = Error;
+
+        fn next_key_seed<K: DeserializeSeed<'de>>(
+            &mut self,
+            seed: K,
+        ) -> Result<Option<K::Value>, Error> {
+            self.next_element_seed(seed)
+        }
+
+        fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+            seed.deserialize(&mut *self.reader)
+        }
+    }
+
+    /// Writes values the way [`Reader`] reads them
+    #[derive(Default)]
+    pub struct Writer(pub Vec<u8>);
+
+    impl Writer {
+        pub fn integer(mut self, value: u64) -> Self {
+            self.0.push(INTEGER);
+            self.0.extend_from_slice(&value.to_le_bytes());
+            self
+        }
+
+        pub fn string(mut self, value: &str) -> Self {
+            self.0.push(STRING);
+            self.0
+                .extend_from_slice(&(value.len() as u32).to_le_bytes());
+            self.0.extend_from_slice(value.as_bytes());
+            self
+        }
+
+        pub fn seq(mut self, len: u32) -> Self {
+            self.0.push(SEQ);
+            self.0.extend_from_slice(&len.to_le_bytes());
+            self
+        }
+
+        pub fn map(mut self, len: u32) -> Self {
+            self.0.push(MAP);
+            self.0.extend_from_slice(&len.to_le_bytes());
+            self
+        }
+    }
+}
+
+use tagged::{from_bytes, Writer};
+
+#[derive(EmptyType, Deserialize, Debug)]
+#[empty(deserialize, fail_safe)]
+struct Service {
+    port: u16,
+    name: String,
+}
+
+#[derive(EmptyType, Deserialize, Debug)]
+#[empty(deserialize, fail_safe, hint)]
+struct HintedService {
+    port: u16,
+    name: String,
+}
+
+#[derive(EmptyType, Deserialize, Debug)]
+#[empty(deserialize)]
+struct Listener {
+    #[empty(fail_safe = "elements")]
+    ports: Vec<u16>,
+    name: String,
+}
+
+// A `Service` whose port is a string
+fn named_port() -> Vec<u8> {
+    Writer::default()
+        .map(2)
+        .string("port")
+        .string("http")
+        .string("name")
+        .string("web")
+        .0
+}
+
+#[test]
+fn the_test_format_is_self_describing() {
+    let bytes = Writer::default().seq(1).string("value").0;
+    from_bytes::<serde::de::IgnoredAny>(&bytes).unwrap();
+}
+
+#[test]
+fn fail_safe_fields_are_read_whole_before_they_fail() {
+    let container: Empty<Service> = from_bytes(&named_port()).unwrap();
+    let paths: Vec<_> = container
+        .diagnostics()
+        .iter()
+        .map(|diagnostic| diagnostic.path().to_string())
+        .collect();
+    assert_eq!(paths, vec!["port"]);
+
+    // the field after the one that failed is still read
+    let service = container.resolve();
+    assert_eq!(service.port, 0);
+    assert_eq!(service.name, "web");
+}
+
+#[test]
+fn hinted_fields_leave_what_they_failed_to_read_in_the_input() {
+    let error = match from_bytes::<Empty<HintedService>>(&named_port()) {
+        Ok(_) => panic!("the rest of the input was read"),
+        Err(error) => error,
+    };
+    // the length of "http" is read as the tag of the next key
+    assert_eq!(
+        error.to_string(),
+        "invalid type: map, expected field identifier"
+    );
+}
+
+#[test]
+fn elements_are_read_whole_before_they_are_dropped() {
+    let bytes = Writer::default()
+        .map(2)
+        .string("ports")
+        .seq(3)
+        .integer(80)
+        .string("http")
+        .integer(443)
+        .string("name")
+        .string("web")
+        .0;
+
+    let mut listener: Empty<Listener> = from_bytes(&bytes).unwrap();
+    let diagnostics = listener.diagnostics();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].path().to_string(), "ports[1]");
+
+    let listener = listener.open();
+    assert_eq!(listener.ports, vec![80, 443]);
+    assert_eq!(listener.name, "web");
+}
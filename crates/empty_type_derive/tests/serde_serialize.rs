@@ -0,0 +1,102 @@
+/*
+ * Copyright [2022] [Kevin Velasco]
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use empty_type::{Empty, EmptyType};
+use empty_type_derive::EmptyType;
+use serde::{Deserialize, Serialize};
+
+#[derive(EmptyType, Deserialize, Serialize)]
+#[empty(deserialize, serialize)]
+struct Draft {
+    title: String,
+    subtitle: Option<String>,
+    published: bool,
+    #[empty(fail_safe)]
+    tags: Vec<String>,
+    #[empty(nested)]
+    author: Author,
+}
+
+#[derive(EmptyType, Deserialize, Serialize)]
+#[empty(deserialize, serialize)]
+#[serde(rename_all = "camelCase")]
+struct Author {
+    first_name: String,
+    last_name: String,
+}
+
+#[derive(EmptyType, Deserialize, Serialize)]
+#[empty(deserialize, serialize)]
+#[serde(tag = "type")]
+enum Event {
+    Created { id: u32, name: String },
+}
+
+#[derive(EmptyType, Deserialize, Serialize)]
+#[empty(deserialize, serialize)]
+struct Pair(u32, String);
+
+fn round_trip<T>(json: &str) -> String
+where
+    T: EmptyType,
+    T::Container: Serialize + for<'de> Deserialize<'de>,
+{
+    let empty: Empty<T> = serde_json::from_str(json).unwrap();
+    serde_json::to_string(&empty).unwrap()
+}
+
+#[test]
+fn unset_fields_are_skipped() {
+    let mut empty = Draft::new_empty();
+    empty.title = Some(String::from("title"));
+
+    let json = serde_json::to_string(&empty).unwrap();
+    assert_eq!(json, r#"{"title":"title"}"#);
+}
+
+#[test]
+fn partial_values_round_trip() {
+    let json = r#"{"title":"title","subtitle":null,"published":true,"tags":["a"],"author":{"firstName":"Ada"}}"#;
+    assert_eq!(round_trip::<Draft>(json), json);
+
+    let json = r#"{"subtitle":"sub","published":false}"#;
+    assert_eq!(round_trip::<Draft>(json), json);
+}
+
+#[test]
+fn enum_containers_round_trip() {
+    let json = r#"{"type":"Created","id":1}"#;
+    assert_eq!(round_trip::<Event>(json), json);
+}
+
+#[test]
+fn unselected_enum_containers_are_not_serialized() {
+    let empty = Event::new_empty();
+    assert!(serde_json::to_string(&empty).is_err());
+}
+
+#[test]
+fn tuple_containers_serialize() {
+    let mut empty = Pair::new_empty();
+    empty.0 .0 = Some(1);
+
+    assert_eq!(serde_json::to_string(&empty).unwrap(), r#"[1,null]"#);
+}
+
+#[test]
+fn tuple_containers_round_trip() {
+    assert_eq!(round_trip::<Pair>(r#"[1,"a"]"#), r#"[1,"a"]"#);
+}
@@ -0,0 +1,9 @@
+use empty_type_derive::EmptyType;
+
+#[derive(EmptyType)]
+#[empty]
+struct Data {
+    value: u32,
+}
+
+fn main() {}
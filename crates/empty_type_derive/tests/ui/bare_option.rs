@@ -0,0 +1,10 @@
+use empty_type_derive::EmptyType;
+
+type Option = std::option::Option<u32>;
+
+#[derive(EmptyType)]
+struct Data {
+    value: Option,
+}
+
+fn main() {}
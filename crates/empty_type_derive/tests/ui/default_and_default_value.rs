@@ -0,0 +1,9 @@
+use empty_type_derive::EmptyType;
+
+#[derive(EmptyType)]
+struct Data {
+    #[empty(default, default_value = 8080)]
+    value: u32,
+}
+
+fn main() {}
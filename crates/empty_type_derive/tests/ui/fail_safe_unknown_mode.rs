@@ -0,0 +1,9 @@
+use empty_type_derive::EmptyType;
+
+#[derive(EmptyType)]
+struct Data {
+    #[empty(fail_safe = "items")]
+    values: Vec<u32>,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use empty_type_derive::EmptyType;
+
+#[derive(EmptyType)]
+struct Data {
+    #[empty(nested = "true")]
+    value: u32,
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use empty_type_derive::EmptyType;
+
+#[derive(EmptyType)]
+struct Data {
+    #[empty(nested, fail_safe = "elements")]
+    inner: Inner,
+}
+
+#[derive(EmptyType)]
+struct Inner {
+    value: u32,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use empty_type_derive::EmptyType;
+
+#[derive(EmptyType)]
+enum Data {
+    #[empty(fail_safe)]
+    Value(u32),
+}
+
+fn main() {}
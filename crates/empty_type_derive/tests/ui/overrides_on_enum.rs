@@ -0,0 +1,10 @@
+use empty_type_derive::EmptyType;
+
+#[derive(EmptyType)]
+#[empty(overrides)]
+enum Transport {
+    Tcp { port: u16 },
+    Unix(String),
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use empty_type_derive::EmptyType;
+
+#[derive(EmptyType)]
+union Data {
+    number: u32,
+    float: f32,
+}
+
+fn main() {}
@@ -17,6 +17,8 @@
 mod test {
     use empty_type_derive::EmptyType;
 
+    // only declared to check that the container compiles
+    #[allow(dead_code)]
     #[derive(EmptyType)]
     pub struct TestStruct {
         value: Option<&'static str>,
@@ -0,0 +1,65 @@
+/*
+ * Copyright [2022] [Kevin Velasco]
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::{field_paths::join, Field};
+
+/// Fields written by [`Container::apply_to`](crate::Container::apply_to).
+///
+/// Fields are recorded by path, e.g. `database.host` for a field of a nested
+/// container or `servers["eu"].port` for an entry of a map. An empty path
+/// stands for the value itself, which is recorded when it's replaced as a
+/// whole, e.g. when an enum switches variants.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Changes {
+    paths: Vec<String>,
+}
+
+impl Changes {
+    /// Nothing was written
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    /// The value was replaced as a whole
+    pub fn replaced() -> Self {
+        Self {
+            paths: vec![String::new()],
+        }
+    }
+
+    /// Records the changes made to `field` under its name
+    pub fn record(&mut self, field: impl Into<Field>, changes: Changes) {
+        let field = field.into();
+        self.paths
+            .extend(changes.paths.iter().map(|path| join(&field, path)));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    pub fn contains(&self, path: &str) -> bool {
+        self.paths.iter().any(|changed| changed == path)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.paths.iter().map(String::as_str)
+    }
+}
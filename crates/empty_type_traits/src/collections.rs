@@ -0,0 +1,504 @@
+/*
+ * Copyright [2022] [Kevin Velasco]
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Containers for the collections and smart pointers of the standard library.
+//!
+//! Collections of containers open element by element, so a `Vec<Empty<T>>`
+//! opens into a `Vec<T>`. Every element is opened before failing and each
+//! failure is attributed to its index or key, e.g. `[2].host`.
+
+use crate::{
+    Changes, Container, Diagnostic, Diff, Field, FieldErrors, FieldPaths, Patch, ResolveError,
+};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::hash::Hash;
+use std::rc::Rc;
+use std::sync::Arc;
+
+// Opens every element, attributing failures to the index of the element
+fn try_open_elements<'a, C, I>(elements: I) -> Result<Vec<C::Value>, ResolveError>
+where
+    C: Container + 'a,
+    I: IntoIterator<Item = &'a mut C>,
+{
+    let mut errors = FieldErrors::default();
+    let values: Vec<_> = elements
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, element)| errors.check(element.try_open_with_meta(index)))
+        .collect();
+
+    if errors.is_empty() {
+        Ok(values)
+    } else {
+        Err(errors.into())
+    }
+}
+
+// Lists the missing fields of every element under the index of the element.
+// An empty sequence resolves, so it's never missing as a whole.
+fn missing_elements<'a, C, I>(elements: I) -> FieldPaths
+where
+    C: Container + 'a,
+    I: IntoIterator<Item = &'a C>,
+{
+    let mut paths = FieldPaths::none();
+    for (index, element) in elements.into_iter().enumerate() {
+        paths.record(index, element.missing_fields());
+    }
+    paths
+}
+
+// Collects the diagnostics of every element under its index
+fn element_diagnostics<'a, C, I>(elements: I) -> Vec<Diagnostic>
+where
+    C: Container + 'a,
+    I: IntoIterator<Item = &'a C>,
+{
+    elements
+        .into_iter()
+        .enumerate()
+        .flat_map(|(index, element)| {
+            element
+                .diagnostics()
+                .into_iter()
+                .map(move |diagnostic| diagnostic.in_field(index))
+        })
+        .collect()
+}
+
+// Opens every entry, attributing failures to the key of the entry
+fn try_open_entries<K, C, I>(entries: I) -> Result<Vec<(K, C::Value)>, ResolveError>
+where
+    K: std::fmt::Debug,
+    C: Container,
+    I: IntoIterator<Item = (K, C)>,
+{
+    let mut errors = FieldErrors::default();
+    let values: Vec<_> = entries
+        .into_iter()
+        .filter_map(|(key, mut element)| {
+            let value = errors.check(element.try_open_with_meta(Field::key(&key)))?;
+            Some((key, value))
+        })
+        .collect();
+
+    if errors.is_empty() {
+        Ok(values)
+    } else {
+        Err(errors.into())
+    }
+}
+
+/// Elements can't be matched up between two sequences, so a sequence is
+/// replaced as a whole when it's merged or applied. An empty sequence is unset.
+impl<C> Container for Vec<C>
+where
+    C: Container,
+{
+    type Value = Vec<C::Value>;
+
+    fn try_open(&mut self) -> Result<Self::Value, ResolveError> {
+        try_open_elements(self.iter_mut())
+    }
+
+    fn is_unset(&self) -> bool {
+        self.is_empty()
+    }
+
+    fn diagnostics(&self) -> Vec<Diagnostic> {
+        element_diagnostics(self)
+    }
+
+    fn missing_fields(&self) -> FieldPaths {
+        missing_elements(self)
+    }
+}
+
+impl<C> Container for VecDeque<C>
+where
+    C: Container,
+{
+    type Value = VecDeque<C::Value>;
+
+    fn try_open(&mut self) -> Result<Self::Value, ResolveError> {
+        try_open_elements(self.iter_mut()).map(VecDeque::from)
+    }
+
+    fn is_unset(&self) -> bool {
+        self.is_empty()
+    }
+
+    fn diagnostics(&self) -> Vec<Diagnostic> {
+        element_diagnostics(self)
+    }
+
+    fn missing_fields(&self) -> FieldPaths {
+        missing_elements(self)
+    }
+}
+
+// An entry of a map is either patched in place or added to the map
+enum Entry<'a, V> {
+    Patched(Patch<'a, V>),
+    Inserted(V),
+}
+
+// `HashMap` and `BTreeMap` only differ in the bounds on their keys
+macro_rules! map_container {
+    ($map:ident, $($key_bounds:tt)+) => {
+        /// Maps merge and apply entry by entry, entries only found in the
+        /// container are added. An empty map is unset.
+        impl<K, C> Container for $map<K, C>
+        where
+            K: $($key_bounds)+ + std::fmt::Debug,
+            C: Container,
+        {
+            type Value = $map<K, C::Value>;
+
+            fn try_open(&mut self) -> Result<Self::Value, ResolveError> {
+                let entries = try_open_entries(std::mem::take(self))?;
+                Ok(entries.into_iter().collect())
+            }
+
+            fn is_unset(&self) -> bool {
+                self.is_empty()
+            }
+
+            fn merge(&mut self, other: Self) {
+                for (key, element) in other {
+                    match self.get_mut(&key) {
+                        Some(current) => current.merge(element),
+                        None => {
+                            self.insert(key, element);
+                        }
+                    }
+                }
+            }
+
+            fn try_patch<'a>(self, target: &Self::Value) -> Result<Patch<'a, Self::Value>, ResolveError>
+            where
+                Self: 'a,
+                Self::Value: 'a,
+            {
+                let mut entries = vec![];
+                for (key, mut element) in self {
+                    let field = Field::key(&key);
+                    let entry = match target.get(&key) {
+                        Some(value) => Entry::Patched(
+                            element
+                                .try_patch(value)
+                                .map_err(|error| error.in_field(field.clone()))?,
+                        ),
+                        None if element.is_unset() => continue,
+                        None => Entry::Inserted(element.try_open_with_meta(field.clone())?),
+                    };
+                    entries.push((key, field, entry));
+                }
+
+                Ok(Patch::new(move |target: &mut Self::Value| {
+                    let mut changes = Changes::none();
+                    for (key, field, entry) in entries {
+                        match entry {
+                            Entry::Patched(patch) => {
+                                if let Some(value) = target.get_mut(&key) {
+                                    changes.record(field, patch.write(value));
+                                }
+                            }
+                            Entry::Inserted(value) => {
+                                target.insert(key, value);
+                                changes.record(field, Changes::replaced());
+                            }
+                        }
+                    }
+                    changes
+                }))
+            }
+
+            fn diagnostics(&self) -> Vec<Diagnostic> {
+                self.iter()
+                    .flat_map(|(key, element)| {
+                        let field = Field::key(key);
+                        element
+                            .diagnostics()
+                            .into_iter()
+                            .map(move |diagnostic| diagnostic.in_field(field.clone()))
+                    })
+                    .collect()
+            }
+
+            fn set_fields(&self) -> FieldPaths {
+                let mut paths = FieldPaths::none();
+                for (key, element) in self {
+                    paths.record(Field::key(key), element.set_fields());
+                }
+                paths
+            }
+
+            fn missing_fields(&self) -> FieldPaths {
+                let mut paths = FieldPaths::none();
+                for (key, element) in self {
+                    paths.record(Field::key(key), element.missing_fields());
+                }
+                paths
+            }
+        }
+    };
+}
+
+map_container!(HashMap, Eq + Hash);
+map_container!(BTreeMap, Ord);
+
+impl<C> Container for Box<C>
+where
+    C: Container,
+{
+    type Value = Box<C::Value>;
+
+    fn try_open(&mut self) -> Result<Self::Value, ResolveError> {
+        self.as_mut().try_open().map(Box::new)
+    }
+
+    fn is_unset(&self) -> bool {
+        self.as_ref().is_unset()
+    }
+
+    fn merge(&mut self, other: Self) {
+        self.as_mut().merge(*other)
+    }
+
+    fn try_patch<'a>(self, target: &Self::Value) -> Result<Patch<'a, Self::Value>, ResolveError>
+    where
+        Self: 'a,
+        Self::Value: 'a,
+    {
+        let patch = (*self).try_patch(target.as_ref())?;
+        Ok(Patch::new(move |target: &mut Self::Value| {
+            patch.write(target.as_mut())
+        }))
+    }
+
+    fn diagnostics(&self) -> Vec<Diagnostic> {
+        self.as_ref().diagnostics()
+    }
+
+    fn set_fields(&self) -> FieldPaths {
+        self.as_ref().set_fields()
+    }
+
+    fn missing_fields(&self) -> FieldPaths {
+        self.as_ref().missing_fields()
+    }
+}
+
+impl<C> Diff for Box<C>
+where
+    C: Diff,
+{
+    fn diff(old: &Self::Value, new: &Self::Value) -> Self {
+        Box::new(C::diff(old, new))
+    }
+}
+
+// Shared containers are cloned when opened while they're shared, and are
+// replaced as a whole when merged or applied
+macro_rules! shared_container {
+    ($pointer:ident) => {
+        impl<C> Container for $pointer<C>
+        where
+            C: Container + Clone,
+        {
+            type Value = $pointer<C::Value>;
+
+            fn try_open(&mut self) -> Result<Self::Value, ResolveError> {
+                $pointer::make_mut(self).try_open().map($pointer::new)
+            }
+
+            fn is_unset(&self) -> bool {
+                self.as_ref().is_unset()
+            }
+
+            fn diagnostics(&self) -> Vec<Diagnostic> {
+                self.as_ref().diagnostics()
+            }
+
+            fn set_fields(&self) -> FieldPaths {
+                self.as_ref().set_fields()
+            }
+
+            fn missing_fields(&self) -> FieldPaths {
+                self.as_ref().missing_fields()
+            }
+        }
+    };
+}
+
+shared_container!(Rc);
+shared_container!(Arc);
+
+/// Arrays have a fixed length, so they merge and apply element by element.
+/// An array is unset when every element is unset.
+impl<C, const N: usize> Container for [C; N]
+where
+    C: Container,
+{
+    type Value = [C::Value; N];
+
+    fn try_open(&mut self) -> Result<Self::Value, ResolveError> {
+        let values = try_open_elements(self.iter_mut())?;
+        match Self::Value::try_from(values) {
+            Ok(values) => Ok(values),
+            // every element was opened
+            Err(_) => unreachable!(),
+        }
+    }
+
+    fn is_unset(&self) -> bool {
+        self.iter().all(Container::is_unset)
+    }
+
+    fn merge(&mut self, other: Self) {
+        for (current, element) in self.iter_mut().zip(other) {
+            current.merge(element);
+        }
+    }
+
+    fn try_patch<'a>(self, target: &Self::Value) -> Result<Patch<'a, Self::Value>, ResolveError>
+    where
+        Self: 'a,
+        Self::Value: 'a,
+    {
+        let mut patches = vec![];
+        for (index, (element, value)) in self.into_iter().zip(target).enumerate() {
+            let patch = element
+                .try_patch(value)
+                .map_err(|error| error.in_field(index))?;
+            patches.push(patch);
+        }
+
+        Ok(Patch::new(move |target: &mut Self::Value| {
+            let mut changes = Changes::none();
+            for (index, (patch, value)) in patches.into_iter().zip(target).enumerate() {
+                changes.record(index, patch.write(value));
+            }
+            changes
+        }))
+    }
+
+    fn diagnostics(&self) -> Vec<Diagnostic> {
+        element_diagnostics(self)
+    }
+
+    fn missing_fields(&self) -> FieldPaths {
+        missing_elements(self)
+    }
+
+    fn set_fields(&self) -> FieldPaths {
+        let mut paths = FieldPaths::none();
+        for (index, element) in self.iter().enumerate() {
+            paths.record(index, element.set_fields());
+        }
+        paths
+    }
+}
+
+// Tuples open like tuple structs, their elements are named by position
+macro_rules! tuple_container {
+    ($($element:ident $index:tt),+) => {
+        /// Tuples merge and apply element by element. A tuple is unset when
+        /// every element is unset.
+        impl<$($element),+> Container for ($($element,)+)
+        where
+            $($element: Container),+
+        {
+            type Value = ($($element::Value,)+);
+
+            #[allow(non_snake_case)]
+            fn try_open(&mut self) -> Result<Self::Value, ResolveError> {
+                let mut errors = FieldErrors::default();
+                $(
+                    let $element = errors.check(self.$index.try_open_with_meta(stringify!($index)));
+                )+
+                match ($($element,)+) {
+                    ($(Some($element),)+) => Ok(($($element,)+)),
+                    _ => Err(errors.into()),
+                }
+            }
+
+            fn is_unset(&self) -> bool {
+                $(self.$index.is_unset())&&+
+            }
+
+            fn merge(&mut self, other: Self) {
+                $(self.$index.merge(other.$index);)+
+            }
+
+            #[allow(non_snake_case)]
+            fn try_patch<'a>(self, target: &Self::Value) -> Result<Patch<'a, Self::Value>, ResolveError>
+            where
+                Self: 'a,
+                Self::Value: 'a,
+            {
+                $(
+                    let $element = self
+                        .$index
+                        .try_patch(&target.$index)
+                        .map_err(|error| error.in_field(stringify!($index)))?;
+                )+
+
+                Ok(Patch::new(move |target: &mut Self::Value| {
+                    let mut changes = Changes::none();
+                    $(changes.record(stringify!($index), $element.write(&mut target.$index));)+
+                    changes
+                }))
+            }
+
+            fn diagnostics(&self) -> Vec<Diagnostic> {
+                let mut diagnostics = vec![];
+                $(
+                    diagnostics.extend(
+                        self.$index
+                            .diagnostics()
+                            .into_iter()
+                            .map(|diagnostic| diagnostic.in_field(stringify!($index))),
+                    );
+                )+
+                diagnostics
+            }
+
+            fn set_fields(&self) -> FieldPaths {
+                let mut paths = FieldPaths::none();
+                $(paths.record(stringify!($index), self.$index.set_fields());)+
+                paths
+            }
+
+            fn missing_fields(&self) -> FieldPaths {
+                let mut paths = FieldPaths::none();
+                $(paths.record(stringify!($index), self.$index.missing_fields());)+
+                paths
+            }
+        }
+    };
+}
+
+tuple_container!(A 0);
+tuple_container!(A 0, B 1);
+tuple_container!(A 0, B 1, C 2);
+tuple_container!(A 0, B 1, C 2, D 3);
+tuple_container!(A 0, B 1, C 2, D 3, E 4);
+tuple_container!(A 0, B 1, C 2, D 3, E 4, F 5);
+tuple_container!(A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+tuple_container!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
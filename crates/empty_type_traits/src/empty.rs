@@ -14,8 +14,9 @@
  * limitations under the License.
  */
 
-use crate::{Container, EmptyType};
-use std::error::Error;
+use crate::{Changes, Container, Diagnostic, Diff, EmptyType, FieldPaths, Patch, ResolveError};
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{Hash, Hasher};
 use std::ops::{Deref, DerefMut};
 
 pub struct Empty<F>(pub F::Container, pub std::marker::PhantomData<F>)
@@ -33,9 +34,68 @@ where
         }
     }
 
-    pub fn try_resolve(mut self) -> Result<<Self as Container>::Value, Box<dyn std::error::Error>> {
+    /// Layers `higher` on top of this container. Fields set in `higher` win,
+    /// nested containers are merged recursively.
+    pub fn overlay(mut self, higher: Empty<F>) -> Self {
+        self.merge(higher);
+        self
+    }
+
+    /// Writes the fields set in this container into `target`, leaving unset
+    /// fields untouched. Returns the fields that were written.
+    pub fn apply_to(
+        self,
+        target: &mut <Self as Container>::Value,
+    ) -> Result<Changes, ResolveError> {
+        Container::apply_to(self, target)
+    }
+
+    /// Every error swallowed by a fail-safe field of this container or of a
+    /// nested container, e.g. a field which fell back to its default because
+    /// it failed to deserialize
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        Container::diagnostics(self)
+    }
+
+    pub fn try_resolve(mut self) -> Result<<Self as Container>::Value, ResolveError> {
         self.try_open()
     }
+
+    /// Paths to the fields which still have to be set for the container to
+    /// resolve, e.g. `database.host`. Fields with a default and optional
+    /// fields aren't missing.
+    pub fn missing_fields(&self) -> Vec<String> {
+        Container::missing_fields(self)
+            .iter()
+            .map(str::to_owned)
+            .collect()
+    }
+
+    /// Paths to the fields which are set, e.g. `database.host`
+    pub fn set_fields(&self) -> Vec<String> {
+        Container::set_fields(self)
+            .iter()
+            .map(str::to_owned)
+            .collect()
+    }
+
+    /// Whether every field that has to be set is set, so the container
+    /// resolves
+    pub fn is_complete(&self) -> bool {
+        Container::missing_fields(self).is_empty()
+    }
+
+    /// The share of fields which are set, out of those set and those still
+    /// missing. `1.0` once the container is complete.
+    pub fn filled_ratio(&self) -> f64 {
+        let set = Container::set_fields(self).len();
+        let missing = Container::missing_fields(self).len();
+        if set + missing == 0 {
+            1.0
+        } else {
+            set as f64 / (set + missing) as f64
+        }
+    }
 }
 
 #[cfg(feature = "serde")]
@@ -54,6 +114,104 @@ where
     }
 }
 
+#[cfg(feature = "serde")]
+impl<'de, F> crate::DeserializeHinted<'de> for Empty<F>
+where
+    F: EmptyType,
+    F::Container: serde::Deserialize<'de>,
+{
+    // the fields of the container are hinted by its own derive
+    fn deserialize_hinted<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        <Self as serde::Deserialize>::deserialize(deserializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<F> serde::Serialize for Empty<F>
+where
+    F: EmptyType,
+    F::Container: serde::Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<F> From<F> for Empty<F>
+where
+    F: EmptyType,
+    F::Container: From<F>,
+{
+    fn from(value: F) -> Self {
+        value.into_empty()
+    }
+}
+
+impl<F> Default for Empty<F>
+where
+    F: EmptyType,
+{
+    fn default() -> Self {
+        F::new_empty()
+    }
+}
+
+// The following impls only depend on the container, the source type
+// doesn't have to implement them
+
+impl<F> Debug for Empty<F>
+where
+    F: EmptyType,
+    F::Container: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Empty").field(&self.0).finish()
+    }
+}
+
+impl<F> Clone for Empty<F>
+where
+    F: EmptyType,
+    F::Container: Clone,
+{
+    fn clone(&self) -> Self {
+        Self(self.0.clone(), Default::default())
+    }
+}
+
+impl<F> PartialEq for Empty<F>
+where
+    F: EmptyType,
+    F::Container: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<F> Eq for Empty<F>
+where
+    F: EmptyType,
+    F::Container: Eq,
+{
+}
+
+impl<F> Hash for Empty<F>
+where
+    F: EmptyType,
+    F::Container: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state)
+    }
+}
+
 impl<F> Deref for Empty<F>
 where
     F: EmptyType,
@@ -80,7 +238,45 @@ where
 {
     type Value = <<F as EmptyType>::Container as Container>::Value;
 
-    fn try_open(&mut self) -> Result<Self::Value, Box<dyn Error>> {
+    fn try_open(&mut self) -> Result<Self::Value, ResolveError> {
         self.0.try_open()
     }
+
+    fn is_unset(&self) -> bool {
+        self.0.is_unset()
+    }
+
+    fn merge(&mut self, other: Self) {
+        self.0.merge(other.0)
+    }
+
+    fn try_patch<'a>(self, target: &Self::Value) -> Result<Patch<'a, Self::Value>, ResolveError>
+    where
+        Self: 'a,
+        Self::Value: 'a,
+    {
+        self.0.try_patch(target)
+    }
+
+    fn diagnostics(&self) -> Vec<Diagnostic> {
+        self.0.diagnostics()
+    }
+
+    fn set_fields(&self) -> FieldPaths {
+        self.0.set_fields()
+    }
+
+    fn missing_fields(&self) -> FieldPaths {
+        self.0.missing_fields()
+    }
+}
+
+impl<F> Diff for Empty<F>
+where
+    F: EmptyType,
+    F::Container: Diff,
+{
+    fn diff(old: &Self::Value, new: &Self::Value) -> Self {
+        Self(Diff::diff(old, new), Default::default())
+    }
 }
@@ -0,0 +1,214 @@
+/*
+ * Copyright [2022] [Kevin Velasco]
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::{Empty, EmptyType, Fallible};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// A snapshot of environment variables to fill containers from
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Env {
+    vars: BTreeMap<String, String>,
+}
+
+impl Env {
+    /// The variables of the current process. Variables which aren't valid
+    /// unicode are left out.
+    pub fn from_process() -> Self {
+        std::env::vars_os()
+            .filter_map(|(name, value)| Some((name.into_string().ok()?, value.into_string().ok()?)))
+            .collect()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.vars.get(name).map(String::as_str)
+    }
+
+    /// Parses the variable `name` with [`FromStr`]. A variable which fails to
+    /// parse is recorded in `errors`.
+    pub fn parse<T>(&self, name: &str, errors: &mut EnvError) -> Option<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let value = self.get(name)?;
+        match value.parse() {
+            Ok(value) => Some(value),
+            Err(error) => {
+                errors.record(name, error);
+                None
+            }
+        }
+    }
+
+    /// Deserializes the variable `name` from its string, e.g. into a unit
+    /// variant of an enum. A variable which fails to deserialize is recorded
+    /// in `errors`.
+    #[cfg(feature = "serde")]
+    #[cfg_attr(docs_rs, doc(cfg(feature = "serde")))]
+    pub fn deserialize<T>(&self, name: &str, errors: &mut EnvError) -> Option<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        use serde::de::{value, IntoDeserializer};
+
+        let value = self.get(name)?;
+        let deserializer = IntoDeserializer::<'_, value::Error>::into_deserializer(value);
+        match T::deserialize(deserializer) {
+            Ok(value) => Some(value),
+            Err(error) => {
+                errors.record(name, error);
+                None
+            }
+        }
+    }
+}
+
+impl<K, V> FromIterator<(K, V)> for Env
+where
+    K: Into<String>,
+    V: Into<String>,
+{
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(vars: I) -> Self {
+        Self {
+            vars: vars
+                .into_iter()
+                .map(|(name, value)| (name.into(), value.into()))
+                .collect(),
+        }
+    }
+}
+
+/// Environment variables which failed to parse, with the reason for each
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EnvError {
+    variables: Vec<(String, String)>,
+}
+
+impl EnvError {
+    /// Keeps the reason the variable `name` failed to parse
+    pub fn record(&mut self, name: &str, reason: impl Display) {
+        self.variables.push((name.to_owned(), reason.to_string()));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.variables.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.variables.len()
+    }
+
+    /// The name of every variable which failed to parse with the reason
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.variables
+            .iter()
+            .map(|(name, reason)| (name.as_str(), reason.as_str()))
+    }
+}
+
+impl Display for EnvError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.variables.as_slice() {
+            [(name, reason)] => write!(f, "Failed to parse `{}`: {}", name, reason),
+            variables => {
+                write!(f, "Failed to parse {} variables:", variables.len())?;
+                for (name, reason) in variables {
+                    write!(f, "\n- `{}`: {}", name, reason)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Error for EnvError {}
+
+/// Containers which can be filled from environment variables. Derived with
+/// `#[empty(env)]`.
+///
+/// A field is read from the variable named after it in upper case, following
+/// `prefix`. The fields of a nested container follow the name of the field
+/// holding it and `__`, e.g. `APP_DATABASE__PORT` for `database.port` under
+/// the prefix `APP_`.
+pub trait FromEnv {
+    /// Sets every field found in `env`. Variables which fail to parse are
+    /// recorded in `errors` and leave their field untouched.
+    fn set_from_env(&mut self, prefix: &str, env: &Env, errors: &mut EnvError);
+}
+
+impl<F> FromEnv for Empty<F>
+where
+    F: EmptyType,
+    F::Container: FromEnv,
+{
+    fn set_from_env(&mut self, prefix: &str, env: &Env, errors: &mut EnvError) {
+        self.0.set_from_env(prefix, env, errors)
+    }
+}
+
+impl<C> FromEnv for Fallible<C>
+where
+    C: FromEnv,
+{
+    fn set_from_env(&mut self, prefix: &str, env: &Env, errors: &mut EnvError) {
+        self.value.set_from_env(prefix, env, errors)
+    }
+}
+
+impl<F> Empty<F>
+where
+    F: EmptyType,
+    F::Container: FromEnv,
+{
+    /// A container with the fields set which are found in the environment of
+    /// the process, e.g. `APP_PORT` for the field `port` under the prefix
+    /// `APP`. Fails with every variable which couldn't be parsed.
+    pub fn from_env(prefix: &str) -> Result<Self, EnvError> {
+        Self::from_env_snapshot(prefix, &Env::from_process())
+    }
+
+    /// Like [`Empty::from_env`], reading from `vars` rather than from the
+    /// environment of the process
+    pub fn from_env_vars<I, K, V>(prefix: &str, vars: I) -> Result<Self, EnvError>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        Self::from_env_snapshot(prefix, &vars.into_iter().collect())
+    }
+
+    fn from_env_snapshot(prefix: &str, env: &Env) -> Result<Self, EnvError> {
+        let prefix = if prefix.is_empty() {
+            String::new()
+        } else {
+            format!("{}_", prefix)
+        };
+
+        let mut empty = F::new_empty();
+        let mut errors = EnvError::default();
+        empty.set_from_env(&prefix, env, &mut errors);
+
+        if errors.is_empty() {
+            Ok(empty)
+        } else {
+            Err(errors)
+        }
+    }
+}
@@ -0,0 +1,353 @@
+/*
+ * Copyright [2022] [Kevin Velasco]
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::error::Error;
+use std::fmt::{self, Debug, Display, Formatter};
+
+/// A member of a container, named for fields, indexed for the elements of a
+/// sequence and keyed for the entries of a map
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Field {
+    Named(&'static str),
+    Index(usize),
+    /// The `Debug` representation of a map key, e.g. `"eu"`
+    Key(String),
+}
+
+impl Field {
+    pub fn key<K: Debug>(key: &K) -> Self {
+        Field::Key(format!("{:?}", key))
+    }
+}
+
+impl From<&'static str> for Field {
+    fn from(name: &'static str) -> Self {
+        Field::Named(name)
+    }
+}
+
+impl From<usize> for Field {
+    fn from(index: usize) -> Self {
+        Field::Index(index)
+    }
+}
+
+impl Display for Field {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Field::Named(name) => f.write_str(name),
+            Field::Index(index) => write!(f, "[{}]", index),
+            Field::Key(key) => write!(f, "[{}]", key),
+        }
+    }
+}
+
+/// The path from a resolved container to one of its members, through nested
+/// containers and collections, e.g. `database.replicas[2].host` or
+/// `servers["eu"].port`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct FieldPath(Vec<Field>);
+
+impl FieldPath {
+    pub fn new(segments: Vec<Field>) -> Self {
+        Self(segments)
+    }
+
+    pub fn segments(&self) -> &[Field] {
+        &self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl Display for FieldPath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (position, segment) in self.0.iter().enumerate() {
+            if position > 0 && matches!(segment, Field::Named(_)) {
+                f.write_str(".")?;
+            }
+            Display::fmt(segment, f)?;
+        }
+        Ok(())
+    }
+}
+
+/// An error which was swallowed rather than returned, e.g. by a fail-safe
+/// field which fell back to its default when it failed to deserialize
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Diagnostic {
+    path: FieldPath,
+    message: String,
+}
+
+impl Diagnostic {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            path: FieldPath::default(),
+            message: message.into(),
+        }
+    }
+
+    /// The path to the field the error was swallowed by, from the container
+    /// the diagnostics were collected from
+    pub fn path(&self) -> &FieldPath {
+        &self.path
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Attributes the diagnostic to `field` of the container holding it
+    pub fn in_field(mut self, field: impl Into<Field>) -> Self {
+        self.path.0.insert(0, field.into());
+        self
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            f.write_str(&self.message)
+        } else {
+            write!(f, "`{}`: {}", self.path, self.message)
+        }
+    }
+}
+
+/// Why a container failed to resolve.
+///
+/// Errors are `Send + Sync + 'static`, so they can be passed across threads
+/// and converted into other error types with `?`.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ResolveError {
+    /// A container held nothing. `field` is filled in by the container holding it.
+    Missing { field: Option<Field> },
+    /// A field holding a container failed to resolve
+    Nested {
+        field: Field,
+        source: Box<ResolveError>,
+    },
+    /// No variant of an enum container was selected
+    Unselected { name: &'static str },
+    /// Several fields of a container failed to resolve
+    Fields(FieldErrors),
+    /// Any other failure, e.g. a failed validation
+    Custom(Box<dyn Error + Send + Sync + 'static>),
+}
+
+impl ResolveError {
+    /// An error for a container which holds nothing
+    pub fn missing() -> Self {
+        ResolveError::Missing { field: None }
+    }
+
+    pub fn custom<E>(error: E) -> Self
+    where
+        E: Into<Box<dyn Error + Send + Sync + 'static>>,
+    {
+        ResolveError::Custom(error.into())
+    }
+
+    /// Every failure behind this error with the full path to the field that
+    /// failed, e.g. `database.replicas[2].host`
+    pub fn failures(&self) -> Vec<(FieldPath, &ResolveError)> {
+        let mut failures = vec![];
+        self.collect_failures(&mut vec![], &mut failures);
+        failures
+    }
+
+    /// The full path to the field that failed. When several fields failed,
+    /// the path to the first one.
+    pub fn path(&self) -> FieldPath {
+        self.failures()
+            .into_iter()
+            .next()
+            .map(|(path, _)| path)
+            .unwrap_or_default()
+    }
+
+    fn collect_failures<'a>(
+        &'a self,
+        path: &mut Vec<Field>,
+        failures: &mut Vec<(FieldPath, &'a ResolveError)>,
+    ) {
+        match self {
+            ResolveError::Nested { field, source } => {
+                path.push(field.clone());
+                source.collect_failures(path, failures);
+                path.pop();
+            }
+            ResolveError::Fields(errors) => errors
+                .iter()
+                .for_each(|error| error.collect_failures(path, failures)),
+            ResolveError::Missing { field: Some(field) } => {
+                let mut path = path.clone();
+                path.push(field.clone());
+                failures.push((FieldPath(path), self));
+            }
+            error => failures.push((FieldPath(path.clone()), error)),
+        }
+    }
+
+    /// Attributes the error to `field` of the container which failed to open
+    pub fn in_field(self, field: impl Into<Field>) -> Self {
+        match self {
+            ResolveError::Missing { field: None } => ResolveError::Missing {
+                field: Some(field.into()),
+            },
+            error => ResolveError::Nested {
+                field: field.into(),
+                source: Box::new(error),
+            },
+        }
+    }
+}
+
+impl Display for ResolveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write_failures(f, &self.failures())
+    }
+}
+
+// A single failure is described on its own, several are listed under a count
+fn write_failures(f: &mut Formatter<'_>, failures: &[(FieldPath, &ResolveError)]) -> fmt::Result {
+    match failures {
+        [(path, error)] => write_failure(f, path, error),
+        failures => {
+            write!(f, "Failed to resolve {} fields:", failures.len())?;
+            for (path, error) in failures {
+                f.write_str("\n- ")?;
+                write_failure(f, path, error)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+// Describes a single failure with the full path to the field that failed
+fn write_failure(f: &mut Formatter<'_>, path: &FieldPath, error: &ResolveError) -> fmt::Result {
+    match error {
+        ResolveError::Missing { .. } if path.is_empty() => {
+            f.write_str("Container opened to `None` value")
+        }
+        ResolveError::Missing { .. } => write!(
+            f,
+            "Failed to resolve field `{}`. Opened to `None` value",
+            path
+        ),
+        ResolveError::Unselected { name } if path.is_empty() => {
+            write!(f, "No variant of `{}` was selected", name)
+        }
+        ResolveError::Unselected { name } => write!(
+            f,
+            "Failed to resolve field `{}`: no variant of `{}` was selected",
+            path, name
+        ),
+        ResolveError::Custom(error) if path.is_empty() => Display::fmt(error, f),
+        ResolveError::Custom(error) => write!(f, "Failed to resolve field `{}`: {}", path, error),
+        // failures only hold the errors at the end of a path
+        ResolveError::Nested { .. } | ResolveError::Fields(_) => unreachable!(),
+    }
+}
+
+impl Error for ResolveError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ResolveError::Nested { source, .. } => Some(source.as_ref()),
+            ResolveError::Custom(error) => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<&str> for ResolveError {
+    fn from(message: &str) -> Self {
+        ResolveError::custom(message)
+    }
+}
+
+impl From<String> for ResolveError {
+    fn from(message: String) -> Self {
+        ResolveError::custom(message)
+    }
+}
+
+impl From<Box<dyn Error + Send + Sync + 'static>> for ResolveError {
+    fn from(error: Box<dyn Error + Send + Sync + 'static>) -> Self {
+        ResolveError::Custom(error)
+    }
+}
+
+/// Every field of a container which failed to resolve.
+///
+/// Derived containers open all of their fields before failing, so a single
+/// error lists every missing field. Containers marked with
+/// `#[empty(fail_fast)]` return the first failure instead.
+#[derive(Debug, Default)]
+pub struct FieldErrors {
+    errors: Vec<ResolveError>,
+}
+
+impl FieldErrors {
+    /// Keeps the error of a field which failed to resolve
+    pub fn check<V>(&mut self, result: Result<V, ResolveError>) -> Option<V> {
+        match result {
+            Ok(value) => Some(value),
+            Err(error) => {
+                self.errors.push(error);
+                None
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &ResolveError> {
+        self.errors.iter()
+    }
+}
+
+impl Display for FieldErrors {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let failures: Vec<_> = self
+            .errors
+            .iter()
+            .flat_map(ResolveError::failures)
+            .collect();
+        write_failures(f, &failures)
+    }
+}
+
+// A single failure is reported on its own
+impl From<FieldErrors> for ResolveError {
+    fn from(mut errors: FieldErrors) -> Self {
+        if errors.len() == 1 {
+            errors.errors.remove(0)
+        } else {
+            ResolveError::Fields(errors)
+        }
+    }
+}
@@ -14,33 +14,54 @@
  * limitations under the License.
  */
 
-use crate::Container;
+use crate::{Container, Diagnostic, Diff, FieldPaths, Patch, ResolveError};
 
-use std::error::Error;
-use std::fmt::Formatter;
-use std::marker::PhantomData;
 use std::ops::{Deref, DerefMut};
 
+#[cfg(feature = "serde")]
+use std::{fmt::Formatter, marker::PhantomData};
+
 #[cfg(feature = "serde")]
 use serde::de::{
     self,
-    value::{self, MapAccessDeserializer, SeqAccessDeserializer},
+    value::{self, EnumAccessDeserializer, MapAccessDeserializer, SeqAccessDeserializer},
     Deserialize, Deserializer, EnumAccess, IntoDeserializer, MapAccess, SeqAccess, Visitor,
 };
 
-pub struct Fallible<T>(T);
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Fallible<T> {
+    pub(crate) value: T,
+    error: Option<Diagnostic>,
+}
+
+impl<T> Fallible<T> {
+    /// The error swallowed when this value failed to deserialize and fell
+    /// back to its default
+    pub fn error(&self) -> Option<&Diagnostic> {
+        self.error.as_ref()
+    }
+
+    // A value which failed to deserialize with `message` and fell back to `value`
+    #[cfg(feature = "serde")]
+    pub(crate) fn fallen_back(value: T, message: impl Into<String>) -> Self {
+        Self {
+            value,
+            error: Some(Diagnostic::new(message)),
+        }
+    }
+}
 
 impl<T> Deref for Fallible<T> {
     type Target = T;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.value
     }
 }
 
 impl<T> DerefMut for Fallible<T> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+        &mut self.value
     }
 }
 
@@ -55,19 +76,115 @@ where
     where
         D: Deserializer<'de>,
     {
-        let visitor: DelegateVisitor<T> = DelegateVisitor {
-            original_type: Default::default(),
-        };
+        Ok(Self::read(deserialize_any_fallibly(deserializer)))
+    }
+}
 
-        let value = deserializer.deserialize_any(visitor);
+/// Fail-safe containers which can be deserialized by the hint of the type they
+/// hold rather than with `deserialize_any`. Formats which aren't
+/// self-describing, like bincode or postcard, reject `deserialize_any`.
+/// Derived for the fields of `#[empty(fail_safe, hint)]` containers.
+///
+/// A value which fails part way through isn't skipped, the rest of the input
+/// is only readable when the format can tell where the value ended.
+#[cfg(feature = "serde")]
+#[cfg_attr(docs_rs, doc(cfg(feature = "serde")))]
+pub trait DeserializeHinted<'de>: Sized {
+    fn deserialize_hinted<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>;
+}
 
-        Ok(Self(value.unwrap_or_default()))
+/// Deserializes `T` by the hint of the type it holds, for
+/// `#[serde(deserialize_with = "empty_type::deserialize_hinted")]`
+#[cfg(feature = "serde")]
+#[cfg_attr(docs_rs, doc(cfg(feature = "serde")))]
+pub fn deserialize_hinted<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: DeserializeHinted<'de>,
+    D: Deserializer<'de>,
+{
+    T::deserialize_hinted(deserializer)
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T> DeserializeHinted<'de> for Fallible<T>
+where
+    T: Default,
+    T: DeserializeHinted<'de>,
+{
+    fn deserialize_hinted<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = T::deserialize_hinted(deserializer).map_err(|error| error.to_string());
+        Ok(Self::read(value))
     }
 }
 
+#[cfg(feature = "serde")]
+impl<'de, T> DeserializeHinted<'de> for Option<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize_hinted<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Self::deserialize(deserializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T> Fallible<T>
+where
+    T: Default,
+{
+    // The value that was read, or the default of `T` when it failed
+    fn read(value: Result<T, String>) -> Self {
+        match value {
+            Ok(value) => Self::from(value),
+            Err(message) => Self::fallen_back(T::default(), message),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T> serde::Serialize for Fallible<T>
+where
+    T: serde::Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.value.serialize(serializer)
+    }
+}
+
+// Deserializes `T` from whatever value is next in the input. The value is read
+// as a whole even when it isn't a `T`, and its failure is returned rather than
+// failing the deserializer.
+#[cfg(feature = "serde")]
+pub(crate) fn deserialize_any_fallibly<'de, T, D>(deserializer: D) -> Result<T, String>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    let visitor: DelegateVisitor<T> = DelegateVisitor {
+        original_type: Default::default(),
+    };
+
+    deserializer
+        .deserialize_any(visitor)
+        .map_err(|error| error.to_string())
+        .and_then(|value| value)
+}
+
 // The delegate visitor is will delegate any value it extracts out
 // of the deserializer into the passed type. When it fails, it will
-// return the default value T
+// return the message of the error it swallowed
+#[cfg(feature = "serde")]
 struct DelegateVisitor<T> {
     original_type: PhantomData<T>,
 }
@@ -75,9 +192,9 @@ struct DelegateVisitor<T> {
 #[cfg(feature = "serde")]
 impl<'de, T> Visitor<'de> for DelegateVisitor<T>
 where
-    T: Deserialize<'de> + Default,
+    T: Deserialize<'de>,
 {
-    type Value = T;
+    type Value = Result<T, String>;
 
     fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
         write!(
@@ -91,7 +208,7 @@ where
         E: serde::de::Error,
     {
         let deserializer = IntoDeserializer::<'_, value::Error>::into_deserializer(v);
-        Ok(T::deserialize(deserializer).unwrap_or_default())
+        Ok(replay(deserializer))
     }
 
     fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<Self::Value, E>
@@ -99,70 +216,70 @@ where
         E: serde::de::Error,
     {
         let deserializer = IntoDeserializer::<'_, value::Error>::into_deserializer(v);
-        Ok(T::deserialize(deserializer).unwrap_or_default())
+        Ok(replay(deserializer))
     }
     fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E>
     where
         E: serde::de::Error,
     {
         let deserializer = IntoDeserializer::<'_, value::Error>::into_deserializer(v);
-        Ok(T::deserialize(deserializer).unwrap_or_default())
+        Ok(replay(deserializer))
     }
     fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
     where
         E: serde::de::Error,
     {
         let deserializer = IntoDeserializer::<'_, value::Error>::into_deserializer(v);
-        Ok(T::deserialize(deserializer).unwrap_or_default())
+        Ok(replay(deserializer))
     }
     fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
     where
         E: serde::de::Error,
     {
         let deserializer = IntoDeserializer::<'_, value::Error>::into_deserializer(v);
-        Ok(T::deserialize(deserializer).unwrap_or_default())
+        Ok(replay(deserializer))
     }
     fn visit_char<E>(self, v: char) -> Result<Self::Value, E>
     where
         E: serde::de::Error,
     {
         let deserializer = IntoDeserializer::<'_, value::Error>::into_deserializer(v);
-        Ok(T::deserialize(deserializer).unwrap_or_default())
+        Ok(replay(deserializer))
     }
     fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
     where
         A: EnumAccess<'de>,
     {
-        let variant: Result<T, _> = data.variant().map(|(v, _)| v);
-        Ok(variant.unwrap_or_default())
+        // the variant is replayed with its payload, not just its tag
+        Ok(replay(EnumAccessDeserializer::new(data)))
     }
     fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
     where
         A: MapAccess<'de>,
     {
         let des = MapAccessDeserializer::new(map);
-        Ok(T::deserialize(des).unwrap_or_default())
+        Ok(replay(des))
     }
 
     fn visit_none<E>(self) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        Ok(T::default())
+        Ok(replay_null())
     }
     fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
     where
         D: Deserializer<'de>,
     {
         let value = T::deserialize(deserializer);
-        Ok(value.unwrap_or_default())
+        Ok(value.map_err(|error| error.to_string()))
     }
     fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
     where
         A: SeqAccess<'de>,
     {
         let des = SeqAccessDeserializer::new(seq);
-        Ok(T::deserialize(des).unwrap_or_default())
+        Ok(replay(des))
     }
 
     fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
@@ -170,14 +287,14 @@ where
         D: Deserializer<'de>,
     {
         let value = T::deserialize(deserializer);
-        Ok(value.unwrap_or_default())
+        Ok(value.map_err(|error| error.to_string()))
     }
     fn visit_u128<E>(self, v: u128) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
         let deserializer = IntoDeserializer::<'_, value::Error>::into_deserializer(v);
-        Ok(T::deserialize(deserializer).unwrap_or_default())
+        Ok(replay(deserializer))
     }
 
     fn visit_f32<E>(self, v: f32) -> Result<Self::Value, E>
@@ -185,7 +302,7 @@ where
         E: de::Error,
     {
         let deserializer = IntoDeserializer::<'_, value::Error>::into_deserializer(v);
-        Ok(T::deserialize(deserializer).unwrap_or_default())
+        Ok(replay(deserializer))
     }
 
     fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
@@ -193,14 +310,14 @@ where
         E: de::Error,
     {
         let deserializer = IntoDeserializer::<'_, value::Error>::into_deserializer(v);
-        Ok(T::deserialize(deserializer).unwrap_or_default())
+        Ok(replay(deserializer))
     }
     fn visit_i8<E>(self, v: i8) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
         let deserializer = IntoDeserializer::<'_, value::Error>::into_deserializer(v);
-        Ok(T::deserialize(deserializer).unwrap_or_default())
+        Ok(replay(deserializer))
     }
 
     fn visit_i16<E>(self, v: i16) -> Result<Self::Value, E>
@@ -208,7 +325,7 @@ where
         E: de::Error,
     {
         let deserializer = IntoDeserializer::<'_, value::Error>::into_deserializer(v);
-        Ok(T::deserialize(deserializer).unwrap_or_default())
+        Ok(replay(deserializer))
     }
 
     fn visit_i32<E>(self, v: i32) -> Result<Self::Value, E>
@@ -216,7 +333,7 @@ where
         E: de::Error,
     {
         let deserializer = IntoDeserializer::<'_, value::Error>::into_deserializer(v);
-        Ok(T::deserialize(deserializer).unwrap_or_default())
+        Ok(replay(deserializer))
     }
 
     fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
@@ -224,7 +341,7 @@ where
         E: de::Error,
     {
         let deserializer = IntoDeserializer::<'_, value::Error>::into_deserializer(v);
-        Ok(T::deserialize(deserializer).unwrap_or_default())
+        Ok(replay(deserializer))
     }
 
     fn visit_i128<E>(self, v: i128) -> Result<Self::Value, E>
@@ -232,7 +349,7 @@ where
         E: de::Error,
     {
         let deserializer = IntoDeserializer::<'_, value::Error>::into_deserializer(v);
-        Ok(T::deserialize(deserializer).unwrap_or_default())
+        Ok(replay(deserializer))
     }
 
     fn visit_u8<E>(self, v: u8) -> Result<Self::Value, E>
@@ -240,7 +357,7 @@ where
         E: de::Error,
     {
         let deserializer = IntoDeserializer::<'_, value::Error>::into_deserializer(v);
-        Ok(T::deserialize(deserializer).unwrap_or_default())
+        Ok(replay(deserializer))
     }
 
     fn visit_u16<E>(self, v: u16) -> Result<Self::Value, E>
@@ -248,14 +365,14 @@ where
         E: de::Error,
     {
         let deserializer = IntoDeserializer::<'_, value::Error>::into_deserializer(v);
-        Ok(T::deserialize(deserializer).unwrap_or_default())
+        Ok(replay(deserializer))
     }
     fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
         let deserializer = IntoDeserializer::<'_, value::Error>::into_deserializer(v);
-        Ok(T::deserialize(deserializer).unwrap_or_default())
+        Ok(replay(deserializer))
     }
 
     fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
@@ -263,7 +380,7 @@ where
         E: de::Error,
     {
         let deserializer = IntoDeserializer::<'_, value::Error>::into_deserializer(v);
-        Ok(T::deserialize(deserializer).unwrap_or_default())
+        Ok(replay(deserializer))
     }
 
     fn visit_u32<E>(self, v: u32) -> Result<Self::Value, E>
@@ -271,21 +388,95 @@ where
         E: de::Error,
     {
         let deserializer = IntoDeserializer::<'_, value::Error>::into_deserializer(v);
-        Ok(T::deserialize(deserializer).unwrap_or_default())
+        Ok(replay(deserializer))
     }
     fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
         let deserializer = IntoDeserializer::<'_, value::Error>::into_deserializer(v);
-        Ok(T::deserialize(deserializer).unwrap_or_default())
+        Ok(replay(deserializer))
     }
 
     fn visit_unit<E>(self) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        Ok(T::default())
+        Ok(replay_null())
+    }
+}
+
+// Replays a value the delegate visitor found into `T`
+#[cfg(feature = "serde")]
+fn replay<'de, T, D>(deserializer: D) -> Result<T, String>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    T::deserialize(Present(deserializer)).map_err(|error| error.to_string())
+}
+
+// Replays a `null` the delegate visitor found into `T`. Options take it as
+// `None`, other types fail like they would without the delegate visitor.
+#[cfg(feature = "serde")]
+fn replay_null<'de, T>() -> Result<T, String>
+where
+    T: Deserialize<'de>,
+{
+    let deserializer = IntoDeserializer::<'_, value::Error>::into_deserializer(());
+    T::deserialize(deserializer).map_err(|error| error.to_string())
+}
+
+// Any value the delegate visitor replays has already been found in the input,
+// so it is always `Some` to the containers it is replayed into. serde's value
+// deserializers forward `deserialize_option` to `deserialize_any`, which
+// `Option<T>` rejects.
+#[cfg(feature = "serde")]
+struct Present<D>(D);
+
+#[cfg(feature = "serde")]
+impl<'de, D> Deserializer<'de> for Present<D>
+where
+    D: Deserializer<'de>,
+{
+    type Error = D::Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.0.deserialize_any(visitor)
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_some(self.0)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.0.deserialize_enum(name, variants, visitor)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+impl<T> From<T> for Fallible<T> {
+    fn from(value: T) -> Self {
+        Self { value, error: None }
     }
 }
 
@@ -294,7 +485,7 @@ where
     T: Default,
 {
     fn default() -> Self {
-        Self(T::default())
+        Self::from(T::default())
     }
 }
 
@@ -305,7 +496,61 @@ where
 {
     type Value = V::Value;
 
-    fn try_open(&mut self) -> Result<Self::Value, Box<dyn Error>> {
-        Ok(self.0.open_or_default())
+    fn try_open(&mut self) -> Result<Self::Value, ResolveError> {
+        Ok(self.value.open_or_default())
+    }
+
+    // Falls back to `default` rather than to `Default::default`
+    fn open_or_else<F>(&mut self, default: F) -> Self::Value
+    where
+        F: FnOnce() -> Self::Value,
+    {
+        self.value.open_or_else(default)
+    }
+
+    fn is_unset(&self) -> bool {
+        self.value.is_unset()
+    }
+
+    // The error of the lower layer is kept until the higher layer supplies a
+    // value or fails itself
+    fn merge(&mut self, other: Self) {
+        if other.error.is_some() || !other.value.is_unset() {
+            self.error = other.error;
+        }
+        self.value.merge(other.value);
+    }
+
+    fn try_patch<'a>(self, target: &Self::Value) -> Result<Patch<'a, Self::Value>, ResolveError>
+    where
+        Self: 'a,
+        Self::Value: 'a,
+    {
+        self.value.try_patch(target)
+    }
+
+    fn diagnostics(&self) -> Vec<Diagnostic> {
+        let mut diagnostics: Vec<_> = self.error.iter().cloned().collect();
+        diagnostics.extend(self.value.diagnostics());
+        diagnostics
+    }
+
+    fn set_fields(&self) -> FieldPaths {
+        self.value.set_fields()
+    }
+
+    // Whatever can't be resolved falls back to its default
+    fn missing_fields(&self) -> FieldPaths {
+        FieldPaths::none()
+    }
+}
+
+impl<V> Diff for Fallible<V>
+where
+    V: Diff,
+    V::Value: Default,
+{
+    fn diff(old: &V::Value, new: &V::Value) -> Self {
+        Self::from(V::diff(old, new))
     }
 }
@@ -0,0 +1,382 @@
+/*
+ * Copyright [2022] [Kevin Velasco]
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::{Container, Diagnostic, Diff, ResolveError};
+use std::ops::{Deref, DerefMut};
+
+#[cfg(feature = "serde")]
+use crate::{fallible::deserialize_any_fallibly, DeserializeHinted, Fallible, Field};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer};
+#[cfg(feature = "serde")]
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+#[cfg(feature = "serde")]
+use std::{fmt::Debug, hash::Hash};
+
+/// Container for collections which are fail-safe element by element.
+///
+/// Where a [`Fallible`](crate::Fallible) collection falls back to its default
+/// when a single element fails to deserialize, a `FallibleEach` drops the
+/// elements that fail and keeps the rest. An entry of a map is dropped when
+/// its value fails, a key which fails still fails the whole map. A value which
+/// isn't a collection at all, e.g. `null` or a string, leaves it unset.
+///
+/// The errors of the dropped elements are kept as [`Diagnostic`]s, attributed
+/// to the index or key the element had in the input, and so is the error of a
+/// value which isn't a collection.
+///
+/// On its own, an unset `FallibleEach` fails to resolve like an `Option` does.
+/// Derived fail-safe fields resolve to the default of the collection instead,
+/// e.g. an empty `Vec`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FallibleEach<T> {
+    value: Option<T>,
+    errors: Vec<Diagnostic>,
+}
+
+impl<T> FallibleEach<T> {
+    /// The errors of the elements which were dropped
+    pub fn errors(&self) -> &[Diagnostic] {
+        &self.errors
+    }
+}
+
+impl<T> Default for FallibleEach<T> {
+    fn default() -> Self {
+        Self {
+            value: None,
+            errors: vec![],
+        }
+    }
+}
+
+impl<T> From<T> for FallibleEach<T> {
+    fn from(value: T) -> Self {
+        Self {
+            value: Some(value),
+            errors: vec![],
+        }
+    }
+}
+
+impl<T> Deref for FallibleEach<T> {
+    type Target = Option<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T> DerefMut for FallibleEach<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.value
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T> serde::Deserialize<'de> for FallibleEach<T>
+where
+    T: DeserializeEach<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let each =
+            deserialize_any_fallibly(deserializer).map(|Each(value, errors)| (value, errors));
+        Ok(Self::read(each))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T> DeserializeHinted<'de> for FallibleEach<T>
+where
+    T: DeserializeEach<'de>,
+{
+    fn deserialize_hinted<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let each = T::deserialize_each_hinted(deserializer).map_err(|error| error.to_string());
+        Ok(Self::read(each))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T> FallibleEach<T> {
+    // A collection which failed as a whole is unset, its error is kept
+    fn read(each: Result<(T, Vec<Diagnostic>), String>) -> Self {
+        match each {
+            Ok((value, errors)) => Self {
+                value: Some(value),
+                errors,
+            },
+            Err(message) => Self {
+                value: None,
+                errors: vec![Diagnostic::new(message)],
+            },
+        }
+    }
+}
+
+// A collection read element by element, for `deserialize_any_fallibly`
+#[cfg(feature = "serde")]
+struct Each<T>(T, Vec<Diagnostic>);
+
+#[cfg(feature = "serde")]
+impl<'de, T> Deserialize<'de> for Each<T>
+where
+    T: DeserializeEach<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let (value, errors) = T::deserialize_each(deserializer)?;
+        Ok(Each(value, errors))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T> serde::Serialize for FallibleEach<T>
+where
+    T: serde::Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.value.serialize(serializer)
+    }
+}
+
+impl<T> Container for FallibleEach<T> {
+    type Value = T;
+
+    fn try_open(&mut self) -> Result<Self::Value, ResolveError> {
+        self.value.try_open()
+    }
+
+    fn is_unset(&self) -> bool {
+        self.value.is_none()
+    }
+
+    fn diagnostics(&self) -> Vec<Diagnostic> {
+        self.errors.clone()
+    }
+}
+
+impl<T> Diff for FallibleEach<T>
+where
+    T: PartialEq + Clone,
+{
+    fn diff(old: &T, new: &T) -> Self {
+        Self {
+            value: Diff::diff(old, new),
+            errors: vec![],
+        }
+    }
+}
+
+/// Collections which can be deserialized element by element, dropping the
+/// elements which fail to deserialize. Used by [`FallibleEach`].
+#[cfg(feature = "serde")]
+#[cfg_attr(docs_rs, doc(cfg(feature = "serde")))]
+pub trait DeserializeEach<'de>: Sized {
+    /// The collection without the elements which failed, and their errors
+    fn deserialize_each<D>(deserializer: D) -> Result<(Self, Vec<Diagnostic>), D::Error>
+    where
+        D: Deserializer<'de>;
+
+    /// Like [`deserialize_each`](DeserializeEach::deserialize_each), with the
+    /// elements deserialized by their hint. See [`DeserializeHinted`].
+    fn deserialize_each_hinted<D>(deserializer: D) -> Result<(Self, Vec<Diagnostic>), D::Error>
+    where
+        D: Deserializer<'de>;
+}
+
+// An element which is `None` when it failed to deserialize. Elements are read
+// whole before they're replayed into `T`, unless `HINTED` where they're
+// deserialized by the hint of `T`.
+#[cfg(feature = "serde")]
+struct Element<T, const HINTED: bool>(Fallible<Option<T>>);
+
+#[cfg(feature = "serde")]
+impl<'de, T, const HINTED: bool> Deserialize<'de> for Element<T, HINTED>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = if HINTED {
+            T::deserialize(deserializer).map_err(|error| error.to_string())
+        } else {
+            deserialize_any_fallibly(deserializer).map(|Found(value)| value)
+        };
+
+        Ok(Element(match value {
+            Ok(value) => Fallible::from(Some(value)),
+            Err(message) => Fallible::fallen_back(None, message),
+        }))
+    }
+}
+
+// An element found in the input. Unlike an `Option` it doesn't take `null` as
+// `None`, so a `null` element is dropped with an error like any other element
+// which isn't a `T`.
+#[cfg(feature = "serde")]
+struct Found<T>(T);
+
+#[cfg(feature = "serde")]
+impl<'de, T> Deserialize<'de> for Found<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        T::deserialize(deserializer).map(Found)
+    }
+}
+
+// Keeps the elements which deserialized and the errors of the ones which
+// didn't under `field`
+#[cfg(feature = "serde")]
+fn keep_element<T, const HINTED: bool>(
+    Element(element): Element<T, HINTED>,
+    field: impl FnOnce() -> Field,
+    errors: &mut Vec<Diagnostic>,
+) -> Option<T> {
+    if let Some(error) = element.error() {
+        errors.push(error.clone().in_field(field()));
+    }
+    element.value
+}
+
+#[cfg(feature = "serde")]
+fn deserialize_elements<'de, T, D, const HINTED: bool>(
+    deserializer: D,
+) -> Result<(Vec<T>, Vec<Diagnostic>), D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    let elements = Vec::<Element<T, HINTED>>::deserialize(deserializer)?;
+    let mut errors = vec![];
+    let values = elements
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, element)| keep_element(element, || Field::Index(index), &mut errors))
+        .collect();
+
+    Ok((values, errors))
+}
+
+#[cfg(feature = "serde")]
+macro_rules! deserialize_each_sequence {
+    ($sequence:ident $(, $bounds:path)*) => {
+        impl<'de, T> DeserializeEach<'de> for $sequence<T>
+        where
+            T: Deserialize<'de> $(+ $bounds)*,
+        {
+            fn deserialize_each<D>(deserializer: D) -> Result<(Self, Vec<Diagnostic>), D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                let (values, errors) = deserialize_elements::<T, D, false>(deserializer)?;
+                Ok((values.into_iter().collect(), errors))
+            }
+
+            fn deserialize_each_hinted<D>(
+                deserializer: D,
+            ) -> Result<(Self, Vec<Diagnostic>), D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                let (values, errors) = deserialize_elements::<T, D, true>(deserializer)?;
+                Ok((values.into_iter().collect(), errors))
+            }
+        }
+    };
+}
+
+#[cfg(feature = "serde")]
+deserialize_each_sequence!(Vec);
+#[cfg(feature = "serde")]
+deserialize_each_sequence!(VecDeque);
+#[cfg(feature = "serde")]
+deserialize_each_sequence!(HashSet, Eq, Hash);
+#[cfg(feature = "serde")]
+deserialize_each_sequence!(BTreeSet, Ord);
+
+#[cfg(feature = "serde")]
+macro_rules! deserialize_each_map {
+    ($map:ident $(, $bounds:path)*) => {
+        impl<'de, K, V> DeserializeEach<'de> for $map<K, V>
+        where
+            K: Deserialize<'de> + Debug $(+ $bounds)*,
+            V: Deserialize<'de>,
+        {
+            fn deserialize_each<D>(deserializer: D) -> Result<(Self, Vec<Diagnostic>), D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                let entries = $map::<K, Element<V, false>>::deserialize(deserializer)?;
+                Ok(keep_entries(entries))
+            }
+
+            fn deserialize_each_hinted<D>(
+                deserializer: D,
+            ) -> Result<(Self, Vec<Diagnostic>), D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                let entries = $map::<K, Element<V, true>>::deserialize(deserializer)?;
+                Ok(keep_entries(entries))
+            }
+        }
+    };
+}
+
+#[cfg(feature = "serde")]
+deserialize_each_map!(HashMap, Eq, Hash);
+#[cfg(feature = "serde")]
+deserialize_each_map!(BTreeMap, Ord);
+
+// Keeps the entries whose value deserialized and the errors of the ones which
+// didn't under their key
+#[cfg(feature = "serde")]
+fn keep_entries<K, V, M, const HINTED: bool>(
+    entries: impl IntoIterator<Item = (K, Element<V, HINTED>)>,
+) -> (M, Vec<Diagnostic>)
+where
+    K: Debug,
+    M: FromIterator<(K, V)>,
+{
+    let mut errors = vec![];
+    let entries = entries
+        .into_iter()
+        .filter_map(|(key, value)| {
+            let value = keep_element(value, || Field::key(&key), &mut errors)?;
+            Some((key, value))
+        })
+        .collect();
+
+    (entries, errors)
+}
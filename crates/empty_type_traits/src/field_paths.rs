@@ -0,0 +1,102 @@
+/*
+ * Copyright [2022] [Kevin Velasco]
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::Field;
+
+/// Paths to fields of a container, as listed by
+/// [`Container::set_fields`](crate::Container::set_fields) and
+/// [`Container::missing_fields`](crate::Container::missing_fields).
+///
+/// Fields are listed like [`Changes`](crate::Changes), e.g. `database.host`
+/// for a field of a nested container, `servers[1].port` for an element of a
+/// sequence or `Tcp::port` for a field of an enum's variant. Derived
+/// containers name fields and variants the way serde does, following
+/// `rename` and `rename_all`, e.g. `listenPort`.
+///
+/// A value which is set or missing as a whole is listed under the field
+/// which holds it, or with an empty path when nothing holds it. An enum
+/// without a selected variant is listed under its own name instead, e.g.
+/// `Transport`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FieldPaths {
+    paths: Vec<Listed>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Listed {
+    // The value as a whole, named when it isn't held by a field
+    Whole(&'static str),
+    Field(String),
+}
+
+impl FieldPaths {
+    /// No fields
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    /// The value as a whole
+    pub fn whole() -> Self {
+        Self::named("")
+    }
+
+    /// The value as a whole, listed as `name` when it isn't held by a field
+    pub fn named(name: &'static str) -> Self {
+        Self {
+            paths: vec![Listed::Whole(name)],
+        }
+    }
+
+    /// Lists the paths of `field` under its name
+    pub fn record(&mut self, field: impl Into<Field>, paths: FieldPaths) {
+        let field = field.into();
+        self.paths
+            .extend(paths.paths.into_iter().map(|path| match path {
+                Listed::Whole(_) => Listed::Field(field.to_string()),
+                Listed::Field(path) => Listed::Field(join(&field, &path)),
+            }));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    pub fn contains(&self, path: &str) -> bool {
+        self.iter().any(|listed| listed == path)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.paths.iter().map(|path| match path {
+            Listed::Whole(name) => name,
+            Listed::Field(path) => path.as_str(),
+        })
+    }
+}
+
+// The path of `path` within `field`, e.g. `database.host` or `servers[1]`
+pub(crate) fn join(field: &Field, path: &str) -> String {
+    if path.is_empty() {
+        field.to_string()
+    } else if path.starts_with('[') {
+        format!("{}{}", field, path)
+    } else {
+        format!("{}.{}", field, path)
+    }
+}
@@ -16,21 +16,115 @@
 
 #![cfg_attr(docs_rs, feature(doc_cfg))]
 
+mod changes;
+mod collections;
 mod empty;
+#[cfg(feature = "env")]
+mod env;
+mod errors;
 mod fallible;
+mod fallible_each;
+mod field_paths;
 
 mod optional;
+mod overrides;
+mod patch;
+mod provenance;
 
+pub use changes::*;
 pub use empty::*;
+#[cfg(feature = "env")]
+pub use env::*;
+pub use errors::*;
 pub use fallible::*;
+pub use fallible_each::*;
+pub use field_paths::*;
 pub use optional::*;
-
-use std::error::Error;
+pub use overrides::*;
+pub use patch::*;
+pub use provenance::*;
 
 pub trait Container {
     type Value;
 
-    fn try_open(&mut self) -> Result<Self::Value, Box<dyn std::error::Error>>;
+    fn try_open(&mut self) -> Result<Self::Value, ResolveError>;
+
+    /// Whether the container holds nothing that was set. Unset fields are
+    /// skipped when a container is serialized. Containers which can't tell
+    /// are always considered set.
+    fn is_unset(&self) -> bool {
+        false
+    }
+
+    /// Merges a higher layer into this container. Whatever is set in `other`
+    /// wins, whatever it leaves unset is kept. Derived containers merge field by
+    /// field, other containers are replaced unless `other` is unset.
+    fn merge(&mut self, other: Self)
+    where
+        Self: Sized,
+    {
+        if !other.is_unset() {
+            *self = other;
+        }
+    }
+
+    /// Writes what is set in this container into `target` and leaves the rest of
+    /// it untouched. Derived containers apply field by field, other containers
+    /// replace `target` unless they are unset. Nothing is written unless every
+    /// field which is going to be written opens.
+    fn apply_to(self, target: &mut Self::Value) -> Result<Changes, ResolveError>
+    where
+        Self: Sized,
+    {
+        let patch = self.try_patch(target)?;
+        Ok(patch.write(target))
+    }
+
+    /// Opens what [`Container::apply_to`] writes into `target`, without writing
+    /// it yet. Containers which apply field by field open a patch for each field.
+    fn try_patch<'a>(
+        mut self,
+        _target: &Self::Value,
+    ) -> Result<Patch<'a, Self::Value>, ResolveError>
+    where
+        Self: Sized + 'a,
+        Self::Value: 'a,
+    {
+        if self.is_unset() {
+            return Ok(Patch::none());
+        }
+
+        Ok(Patch::replace(self.try_open()?))
+    }
+
+    /// Errors swallowed by the fail-safe fields of this container, with the
+    /// path to the field which swallowed them
+    fn diagnostics(&self) -> Vec<Diagnostic> {
+        vec![]
+    }
+
+    /// The fields set in this container, which merging it into a lower layer
+    /// writes. Derived containers list their fields by path, other containers
+    /// are set or unset as a whole.
+    fn set_fields(&self) -> FieldPaths {
+        if self.is_unset() {
+            FieldPaths::none()
+        } else {
+            FieldPaths::whole()
+        }
+    }
+
+    /// The fields which fail to resolve because nothing set them. Fields
+    /// which fall back to a default aren't missing. Derived containers list
+    /// their fields by path, other containers are missing as a whole when
+    /// they're unset.
+    fn missing_fields(&self) -> FieldPaths {
+        if self.is_unset() {
+            FieldPaths::whole()
+        } else {
+            FieldPaths::none()
+        }
+    }
 
     fn open(&mut self) -> Self::Value {
         self.try_open().unwrap()
@@ -43,39 +137,45 @@ pub trait Container {
         self.try_open().unwrap_or_default()
     }
 
-    fn try_open_with_meta(
-        &mut self,
-        field_name: &'static str,
-    ) -> Result<Self::Value, Box<dyn std::error::Error>> {
-        self.try_open().map_err(|_| {
-            format!(
-                "Failed to resolve field `{}`. Opened to `None` value",
-                field_name
-            )
-            .into()
-        })
+    /// Opens the container, falling back to the value produced by `default`
+    /// if it can't be opened
+    fn open_or_else<F>(&mut self, default: F) -> Self::Value
+    where
+        F: FnOnce() -> Self::Value,
+        Self: Sized,
+    {
+        self.try_open().unwrap_or_else(|_| default())
     }
 
-    fn open_with_meta(&mut self, field_name: &'static str) -> Self::Value {
-        self.try_open_with_meta(field_name)
-            .expect(concat!(stringify!(field_name), "Failed to resolve"))
+    /// Opens the container held by `field`, attributing a failure to it
+    fn try_open_with_meta(&mut self, field: impl Into<Field>) -> Result<Self::Value, ResolveError>
+    where
+        Self: Sized,
+    {
+        self.try_open().map_err(|error| error.in_field(field))
     }
-}
 
-impl Container for bool {
-    type Value = Self;
-
-    fn try_open(&mut self) -> Result<Self::Value, Box<dyn Error>> {
-        Ok(*self)
+    fn open_with_meta(&mut self, field: impl Into<Field>) -> Self::Value
+    where
+        Self: Sized,
+    {
+        match self.try_open_with_meta(field) {
+            Ok(value) => value,
+            Err(error) => panic!("{}", error),
+        }
     }
 }
 
 impl<V> Container for Option<V> {
     type Value = V;
 
-    fn try_open(&mut self) -> Result<Self::Value, Box<dyn Error>> {
+    fn try_open(&mut self) -> Result<Self::Value, ResolveError> {
         let value = std::mem::take(self);
-        value.ok_or_else(|| "Option opened to `None value`".into())
+        value.ok_or_else(ResolveError::missing)
+    }
+
+    fn is_unset(&self) -> bool {
+        self.is_none()
     }
 }
 
@@ -85,12 +185,54 @@ where
 {
     type Container: Container + Default;
 
+    /// Names of the fields of the type, e.g. `["port", "database"]`. The fields
+    /// of an enum are named after their variant, e.g. `Tcp::port`.
+    const FIELD_NAMES: &'static [&'static str] = &[];
+
     fn new_container() -> Self::Container {
         Default::default()
     }
     fn new_empty() -> Empty<Self> {
         Empty(Self::new_container(), Default::default())
     }
+
+    /// Converts a full value into its container with every field set. This is the
+    /// inverse of [`Container::try_open`]. Derived containers implement `From<Self>`.
+    fn into_empty(self) -> Empty<Self>
+    where
+        Self::Container: From<Self>,
+    {
+        Empty(self.into(), Default::default())
+    }
+
+    /// A container with only the fields that differ between `old` and `new` set.
+    /// Applying it to `old` gives `new`, including fields changed to `false` or `None`.
+    /// Derived with `#[empty(diff)]`.
+    fn diff(old: &Self, new: &Self) -> Empty<Self>
+    where
+        Self::Container: Diff<Value = Self>,
+    {
+        Empty(Diff::diff(old, new), Default::default())
+    }
+}
+
+/// Containers which can hold the difference between two full values
+pub trait Diff: Container {
+    /// A container with whatever differs between `old` and `new` set
+    fn diff(old: &Self::Value, new: &Self::Value) -> Self;
+}
+
+impl<V> Diff for Option<V>
+where
+    V: PartialEq + Clone,
+{
+    fn diff(old: &V, new: &V) -> Self {
+        if old == new {
+            None
+        } else {
+            Some(new.clone())
+        }
+    }
 }
 
 /// Used to deserialize a given type into its coreesponding EmptyType
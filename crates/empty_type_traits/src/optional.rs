@@ -14,15 +14,32 @@
  * limitations under the License.
  */
 
-use crate::Container;
-use std::error::Error;
+use crate::{Container, Diff, FieldPaths, ResolveError};
 use std::ops::{Deref, DerefMut};
 
-pub struct Optional<T>(Option<T>);
+/// Container for source values which are already an [`Option`].
+///
+/// An `Optional` remembers whether it was set separately from its value, so a
+/// value explicitly set to `None` is distinct from one that was never set.
+/// Mutable access through [`DerefMut`] marks the value as set.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Optional<T> {
+    value: Option<T>,
+    set: bool,
+}
 
 impl<T> Default for Optional<T> {
     fn default() -> Self {
-        Self(None)
+        Self {
+            value: None,
+            set: false,
+        }
+    }
+}
+
+impl<T> From<Option<T>> for Optional<T> {
+    fn from(value: Option<T>) -> Self {
+        Self { value, set: true }
     }
 }
 
@@ -36,7 +53,33 @@ where
         D: serde::Deserializer<'de>,
     {
         let value = <Option<T> as serde::Deserialize>::deserialize(deserializer)?;
-        Ok(Self(value))
+        Ok(Self::from(value))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T> crate::DeserializeHinted<'de> for Optional<T>
+where
+    T: serde::Deserialize<'de>,
+{
+    fn deserialize_hinted<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        <Self as serde::Deserialize>::deserialize(deserializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T> serde::Serialize for Optional<T>
+where
+    T: serde::Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.value.serialize(serializer)
     }
 }
 
@@ -44,13 +87,14 @@ impl<T> Deref for Optional<T> {
     type Target = Option<T>;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.value
     }
 }
 
 impl<T> DerefMut for Optional<T> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+        self.set = true;
+        &mut self.value
     }
 }
 
@@ -58,7 +102,29 @@ impl<V> Container for Optional<V> {
     type Value = Option<V>;
 
     /// Optionals will always return an option regardless of the underlying value
-    fn try_open(&mut self) -> Result<Self::Value, Box<dyn Error>> {
-        Ok(self.0.try_open().ok())
+    fn try_open(&mut self) -> Result<Self::Value, ResolveError> {
+        Ok(self.value.try_open().ok())
+    }
+
+    fn is_unset(&self) -> bool {
+        !self.set
+    }
+
+    // An unset optional resolves to `None`
+    fn missing_fields(&self) -> FieldPaths {
+        FieldPaths::none()
+    }
+}
+
+impl<V> Diff for Optional<V>
+where
+    V: PartialEq + Clone,
+{
+    fn diff(old: &Option<V>, new: &Option<V>) -> Self {
+        if old == new {
+            Self::default()
+        } else {
+            Self::from(new.clone())
+        }
     }
 }
@@ -0,0 +1,180 @@
+/*
+ * Copyright [2022] [Kevin Velasco]
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::{Empty, EmptyType, Fallible};
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// A field which couldn't be set from a string by its path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OverrideError {
+    /// No field is found at `path`
+    UnknownPath { path: String },
+    /// The value couldn't be parsed into the type of the field at `path`
+    Invalid { path: String, reason: String },
+    /// An override isn't of the form `path=value`
+    Malformed(String),
+}
+
+impl OverrideError {
+    pub fn unknown_path(path: &str) -> Self {
+        OverrideError::UnknownPath {
+            path: path.to_owned(),
+        }
+    }
+
+    pub fn invalid(path: &str, reason: impl Display) -> Self {
+        OverrideError::Invalid {
+            path: path.to_owned(),
+            reason: reason.to_string(),
+        }
+    }
+
+    /// The path the error was found at, relative to the outermost container
+    pub fn path(&self) -> Option<&str> {
+        match self {
+            OverrideError::UnknownPath { path } | OverrideError::Invalid { path, .. } => Some(path),
+            OverrideError::Malformed(_) => None,
+        }
+    }
+
+    /// Prepends the field of the container this error was found in to its path
+    pub fn in_field(self, field: &str) -> Self {
+        match self {
+            OverrideError::UnknownPath { path } => OverrideError::UnknownPath {
+                path: format!("{}.{}", field, path),
+            },
+            OverrideError::Invalid { path, reason } => OverrideError::Invalid {
+                path: format!("{}.{}", field, path),
+                reason,
+            },
+            malformed => malformed,
+        }
+    }
+}
+
+impl Display for OverrideError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            OverrideError::UnknownPath { path } => write!(f, "No field at `{}`", path),
+            OverrideError::Invalid { path, reason } => {
+                write!(f, "Failed to parse `{}`: {}", path, reason)
+            }
+            OverrideError::Malformed(entry) => {
+                write!(
+                    f,
+                    "Expected an override of the form `path=value`, found `{}`",
+                    entry
+                )
+            }
+        }
+    }
+}
+
+impl Error for OverrideError {}
+
+/// Parses the value of the field `name` with [`FromStr`]
+pub fn parse_override<T>(name: &str, value: &str) -> Result<T, OverrideError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    value
+        .parse()
+        .map_err(|error| OverrideError::invalid(name, error))
+}
+
+/// Deserializes the value of the field `name` from its string, e.g. into a
+/// unit variant of an enum
+#[cfg(feature = "serde")]
+#[cfg_attr(docs_rs, doc(cfg(feature = "serde")))]
+pub fn deserialize_override<T>(name: &str, value: &str) -> Result<T, OverrideError>
+where
+    T: serde::de::DeserializeOwned,
+{
+    use serde::de::{value, IntoDeserializer};
+
+    let deserializer = IntoDeserializer::<'_, value::Error>::into_deserializer(value);
+    T::deserialize(deserializer).map_err(|error| OverrideError::invalid(name, error))
+}
+
+/// Containers whose fields can be set from strings by their path, e.g. from
+/// `--set server.tls.cert=cert.pem` on a command line. Derived with
+/// `#[empty(overrides)]`.
+///
+/// Fields are named as they're deserialized, following `rename` and
+/// `rename_all`, and the fields of nested containers are joined with `.`.
+pub trait SetPath {
+    /// Parses `value` into the field at `path`
+    fn set_path(&mut self, path: &str, value: &str) -> Result<(), OverrideError>;
+}
+
+impl<F> SetPath for Empty<F>
+where
+    F: EmptyType,
+    F::Container: SetPath,
+{
+    fn set_path(&mut self, path: &str, value: &str) -> Result<(), OverrideError> {
+        self.0.set_path(path, value)
+    }
+}
+
+impl<C> SetPath for Fallible<C>
+where
+    C: SetPath,
+{
+    fn set_path(&mut self, path: &str, value: &str) -> Result<(), OverrideError> {
+        self.value.set_path(path, value)
+    }
+}
+
+impl<F> Empty<F>
+where
+    F: EmptyType,
+    F::Container: SetPath,
+{
+    /// Parses `value` into the field at `path`, e.g. `server.tls.cert`
+    pub fn set_path(&mut self, path: &str, value: &str) -> Result<(), OverrideError> {
+        self.0.set_path(path, value)
+    }
+
+    /// Sets every override of the form `path=value`. Every override is tried
+    /// before failing, and every one that fails is returned.
+    pub fn apply_overrides<I, S>(&mut self, overrides: I) -> Result<(), Vec<OverrideError>>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let errors: Vec<_> = overrides
+            .into_iter()
+            .filter_map(|entry| {
+                let entry = entry.as_ref();
+                let result = match entry.split_once('=') {
+                    Some((path, value)) => self.set_path(path, value),
+                    None => Err(OverrideError::Malformed(entry.to_owned())),
+                };
+                result.err()
+            })
+            .collect();
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
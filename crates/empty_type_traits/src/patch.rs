@@ -0,0 +1,60 @@
+/*
+ * Copyright [2022] [Kevin Velasco]
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::Changes;
+
+/// What applying a container writes into a full value, opened before anything
+/// is written.
+///
+/// [`Container::try_patch`](crate::Container::try_patch) opens every field and
+/// variant a container is going to write, so a container which fails to open
+/// leaves the full value untouched. A patch has to be written to the value it
+/// was opened against.
+pub struct Patch<'a, V> {
+    write: Box<dyn FnOnce(&mut V) -> Changes + 'a>,
+}
+
+impl<'a, V> Patch<'a, V> {
+    pub fn new<W>(write: W) -> Self
+    where
+        W: FnOnce(&mut V) -> Changes + 'a,
+    {
+        Self {
+            write: Box::new(write),
+        }
+    }
+
+    /// Writes nothing
+    pub fn none() -> Self {
+        Self::new(|_| Changes::none())
+    }
+
+    /// Replaces the value as a whole
+    pub fn replace(value: V) -> Self
+    where
+        V: 'a,
+    {
+        Self::new(move |target| {
+            *target = value;
+            Changes::replaced()
+        })
+    }
+
+    /// Writes the patch into `target`, returning the fields it wrote
+    pub fn write(self, target: &mut V) -> Changes {
+        (self.write)(target)
+    }
+}
@@ -0,0 +1,197 @@
+/*
+ * Copyright [2022] [Kevin Velasco]
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::{Container, Empty, EmptyType, ResolveError};
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+
+/// The source which last set each field of a layered container, e.g. a file,
+/// an environment variable or `"default"`.
+///
+/// Fields are recorded by path, like [`FieldPaths`](crate::FieldPaths). Its
+/// `Display` is a report of the effective configuration with one field per
+/// line, e.g. `database.host: APP_DATABASE__HOST`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Provenance {
+    sources: BTreeMap<String, String>,
+}
+
+impl Provenance {
+    /// Records `source` for every field set in `layer`, over whatever lower
+    /// layers recorded for them. The fields of a variant `layer` switches away
+    /// from are only forgotten by [`Provenance::retain_set`].
+    pub fn record<C>(&mut self, source: &str, layer: &C)
+    where
+        C: Container,
+    {
+        self.record_with(layer, |_| source.to_owned())
+    }
+
+    /// Like [`Provenance::record`], naming the source of each field from its
+    /// path, e.g. after the environment variable it was read from
+    pub fn record_with<C, S>(&mut self, layer: &C, source: S)
+    where
+        C: Container,
+        S: Fn(&str) -> String,
+    {
+        for path in layer.set_fields().iter() {
+            self.insert(path.to_owned(), source(path));
+        }
+    }
+
+    /// Forgets the sources of fields which aren't set in `container` anymore,
+    /// e.g. the fields of a variant a higher layer switched away from.
+    /// [`Traced`] does this after every layer it merges.
+    pub fn retain_set<C>(&mut self, container: &C)
+    where
+        C: Container,
+    {
+        let set = container.set_fields();
+        self.sources.retain(|path, _| set.contains(path));
+    }
+
+    /// Layers the provenance of a higher layer over this one
+    pub fn merge(&mut self, other: Provenance) {
+        for (path, source) in other.sources {
+            self.insert(path, source);
+        }
+    }
+
+    /// The source which last set the field at `path`
+    pub fn source(&self, path: &str) -> Option<&str> {
+        self.sources.get(path).map(String::as_str)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sources.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.sources.len()
+    }
+
+    /// Every field with its source, ordered by path
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.sources
+            .iter()
+            .map(|(path, source)| (path.as_str(), source.as_str()))
+    }
+
+    // A field set as a whole replaces whatever was recorded within it
+    fn insert(&mut self, path: String, source: String) {
+        self.sources
+            .retain(|recorded, _| !is_within(recorded, &path));
+        self.sources.insert(path, source);
+    }
+}
+
+// Whether `path` is a field of the value at `parent`, e.g. `database.host`
+// of `database` or `servers[0]` of `servers`
+fn is_within(path: &str, parent: &str) -> bool {
+    match path.strip_prefix(parent) {
+        Some(rest) => {
+            (parent.is_empty() && !rest.is_empty())
+                || rest.starts_with('.')
+                || rest.starts_with('[')
+        }
+        None => false,
+    }
+}
+
+impl Display for Provenance {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (position, (path, source)) in self.iter().enumerate() {
+            if position > 0 {
+                f.write_str("\n")?;
+            }
+            write!(f, "{}: {}", path, source)?;
+        }
+        Ok(())
+    }
+}
+
+/// A container layered from named sources, which remembers the source that
+/// last set each of its fields
+pub struct Traced<F>
+where
+    F: EmptyType,
+{
+    empty: Empty<F>,
+    provenance: Provenance,
+}
+
+impl<F> Traced<F>
+where
+    F: EmptyType,
+{
+    pub fn new() -> Self {
+        Self {
+            empty: F::new_empty(),
+            provenance: Provenance::default(),
+        }
+    }
+
+    /// Layers `higher` on top of this container, recording `source` for the
+    /// fields it sets
+    pub fn overlay(self, source: &str, higher: Empty<F>) -> Self {
+        self.overlay_with(higher, |_| source.to_owned())
+    }
+
+    /// Like [`Traced::overlay`], naming the source of each field from its path
+    pub fn overlay_with<S>(mut self, higher: Empty<F>, source: S) -> Self
+    where
+        S: Fn(&str) -> String,
+    {
+        self.provenance.record_with(&higher, source);
+        self.empty.merge(higher);
+        self.provenance.retain_set(&self.empty);
+        self
+    }
+
+    /// Layers another traced container on top of this one, with its provenance
+    pub fn merge(&mut self, other: Traced<F>) {
+        self.empty.merge(other.empty);
+        self.provenance.merge(other.provenance);
+        self.provenance.retain_set(&self.empty);
+    }
+
+    pub fn empty(&self) -> &Empty<F> {
+        &self.empty
+    }
+
+    pub fn provenance(&self) -> &Provenance {
+        &self.provenance
+    }
+
+    pub fn into_parts(self) -> (Empty<F>, Provenance) {
+        (self.empty, self.provenance)
+    }
+
+    /// Resolves the container, keeping the provenance of its fields
+    pub fn try_resolve(self) -> Result<(<Empty<F> as Container>::Value, Provenance), ResolveError> {
+        let value = self.empty.try_resolve()?;
+        Ok((value, self.provenance))
+    }
+}
+
+impl<F> Default for Traced<F>
+where
+    F: EmptyType,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}